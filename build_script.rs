@@ -1,5 +1,12 @@
+use std::env;
+use std::fs;
+use std::path::Path;
 use std::process::Command;
 
+use syntect::dumps::dump_to_uncompressed_file;
+use syntect::parsing::SyntaxSet;
+use walkdir::WalkDir;
+
 fn main() {
     // git tagからバージョンを取得
     let version = get_git_version().unwrap_or_else(|| "dev".to_string());
@@ -10,6 +17,56 @@ fn main() {
     // gitの変更を監視（タグが変わったら再ビルド）
     println!("cargo:rerun-if-changed=.git/HEAD");
     println!("cargo:rerun-if-changed=.git/refs/tags");
+
+    build_syntax_set();
+    build_source_id();
+}
+
+/// `src/`（コード・テンプレート）の内容からビルドIDを計算する
+///
+/// インクリメンタルビルドのキャッシュキーに含め、SSGを変更したら自動でキャッシュを無効化する
+fn build_source_id() {
+    println!("cargo:rerun-if-changed=src");
+
+    let mut files: Vec<_> = WalkDir::new("src")
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .collect();
+    files.sort();
+
+    let mut hasher = blake3::Hasher::new();
+    for path in files {
+        let contents = fs::read(&path).expect("src/ のファイルを読み込めません");
+        hasher.update(path.to_string_lossy().as_bytes());
+        hasher.update(&(contents.len() as u64).to_le_bytes());
+        hasher.update(&contents);
+    }
+    println!(
+        "cargo:rustc-env=DNFOLIO_BUILD_ID={}",
+        hasher.finalize().to_hex()
+    );
+}
+
+/// 既定の構文に `syntaxes/` の .sublime-syntax を追加し、バイナリダンプとして書き出す
+///
+/// 実行時に構文定義（YAML）を解析しないので起動が遅くならない
+fn build_syntax_set() {
+    println!("cargo:rerun-if-changed=syntaxes");
+
+    let mut builder = SyntaxSet::load_defaults_newlines().into_builder();
+    builder
+        .add_from_folder("syntaxes", true)
+        .expect("syntaxes/ の構文定義を読み込めません");
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is not set");
+    dump_to_uncompressed_file(
+        &builder.build(),
+        Path::new(&out_dir).join("syntaxes.packdump"),
+    )
+    .expect("構文定義のダンプを書き出せません");
 }
 
 fn get_git_version() -> Option<String> {
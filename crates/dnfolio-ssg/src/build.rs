@@ -1,25 +1,27 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, OnceLock};
+use std::sync::Arc;
 
 use anyhow::Result;
-use chrono::Datelike;
-use gray_matter::{Matter, ParsedEntity};
+use chrono::{DateTime, Datelike, FixedOffset};
 use maud::{Markup, html};
-use pulldown_cmark::{CowStr, Event, HeadingLevel, Parser, Tag, TagEnd};
+use pulldown_cmark::{
+    BlockQuoteKind, CowStr, Event, HeadingLevel, Parser, Tag, TagEnd, TextMergeWithOffset,
+};
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
-use resvg::usvg::{self, fontdb};
 use slug::slugify;
-use syntect::highlighting::ThemeSet;
-use syntect::html::highlighted_html_for_string;
-use syntect::parsing::SyntaxSet;
 use walkdir::WalkDir;
 
-use crate::models::{Article, Heading, MetaData, Page, TagInfo};
+use crate::cache::{self, BuildCache};
+use crate::config::SiteConfig;
+use crate::models::{Article, Heading, InternalLink, MetaData, Page, ResponsiveImage, TagInfo};
 use crate::templates::base::{ArticlePageConfig, PageConfig};
 use crate::templates::{base, icons, privacy};
-use crate::{dates, ogp, redirects, rss, sitemap, structured_data};
+use crate::{
+    dates, feeds, front_matter, highlight, history, i18n, images, links, math, ogp, publish,
+    reading, redirects, related, series, shortcodes, sitemap, structured_data, tags,
+};
 
 // 年月別グループ化のためのヘルパー構造
 struct YearGroup {
@@ -32,6 +34,24 @@ struct MonthGroup {
     articles: Vec<Article>,
 }
 
+impl YearGroup {
+    /// 年別アーカイブページのURL
+    fn url(&self) -> String {
+        format!("/posts/{}/", self.year)
+    }
+
+    fn article_count(&self) -> usize {
+        self.months.iter().map(|group| group.articles.len()).sum()
+    }
+}
+
+impl MonthGroup {
+    /// 月別アーカイブページのURL
+    fn url(&self, year: i32) -> String {
+        format!("/posts/{}/{:02}/", year, self.month)
+    }
+}
+
 // 記事を年月別にグループ化
 fn group_articles_by_year_month(articles: &[Article]) -> Vec<YearGroup> {
     let mut year_map: HashMap<i32, HashMap<u32, Vec<Article>>> = HashMap::new();
@@ -56,12 +76,12 @@ fn group_articles_by_year_month(articles: &[Article]) -> Vec<YearGroup> {
                 .into_iter()
                 .map(|(month, articles)| MonthGroup { month, articles })
                 .collect();
-            months.sort_by(|a, b| b.month.cmp(&a.month));
+            months.sort_by_key(|group| std::cmp::Reverse(group.month));
             YearGroup { year, months }
         })
         .collect();
 
-    years.sort_by(|a, b| b.year.cmp(&a.year));
+    years.sort_by_key(|group| std::cmp::Reverse(group.year));
     years
 }
 
@@ -77,14 +97,14 @@ fn generate_file_tree_markup(
                 li class="folder-item" {
                     span class="file-tree-item folder-toggle folder-year" {
                         span class="tree-icon tree-icon-folder" { (maud::PreEscaped(icons::folder_open(12))) }
-                        (format!("{}", year_group.year))
+                        a class="folder-link" href=(year_group.url()) { (year_group.year) }
                     }
                     ul {
                         @for month_group in &year_group.months {
                             li class="folder-item" {
                                 span class="file-tree-item folder-toggle folder-month" {
                                     span class="tree-icon tree-icon-folder" { (maud::PreEscaped(icons::folder_open(12))) }
-                                    (format!("{:02}", month_group.month))
+                                    a class="folder-link" href=(month_group.url(year_group.year)) { (format!("{:02}", month_group.month)) }
                                 }
                                 ul {
                                     @for article in &month_group.articles {
@@ -120,105 +140,134 @@ fn generate_file_tree_markup(
     }
 }
 
-static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
-static THEME_SET: OnceLock<ThemeSet> = OnceLock::new();
+/// GitHub形式のアラート（`> [!NOTE]` 等）のタイトル行
+///
+/// blockquote自体はpulldown-cmarkが `markdown-alert-*` クラス付きで出力する
+fn alert_title_html(kind: BlockQuoteKind) -> String {
+    let (icon, label) = match kind {
+        BlockQuoteKind::Note => (icons::info(icons::ICON_SIZE_LG), "Note"),
+        BlockQuoteKind::Tip => (icons::lightbulb(icons::ICON_SIZE_LG), "Tip"),
+        BlockQuoteKind::Important => (icons::message_alert(icons::ICON_SIZE_LG), "Important"),
+        BlockQuoteKind::Warning => (icons::alert_triangle(icons::ICON_SIZE_LG), "Warning"),
+        BlockQuoteKind::Caution => (icons::alert_octagon(icons::ICON_SIZE_LG), "Caution"),
+    };
+    format!(r#"<p class="markdown-alert-title">{icon}{label}</p>"#)
+}
 
-fn get_syntax_set() -> &'static SyntaxSet {
-    SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines)
+/// 記事のslugを決定する（front matterの `slug`、なければファイル名の日付以降）
+pub fn article_slug(input_path: &Path, metadata: Option<&MetaData>) -> String {
+    let file_stem = input_path.file_stem().unwrap().to_string_lossy();
+    metadata
+        .and_then(|m| m.slug.as_ref())
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| {
+            let name_part = file_stem.split('_').skip(1).collect::<Vec<_>>().join("-");
+            if name_part.is_empty() {
+                slugify(&file_stem)
+            } else {
+                slugify(&name_part)
+            }
+        })
 }
 
-fn get_theme_set() -> &'static ThemeSet {
-    THEME_SET.get_or_init(ThemeSet::load_defaults)
+/// 記事本文のMarkdown拡張（buildとcheckで同じ解釈にする）
+pub fn markdown_options() -> pulldown_cmark::Options {
+    let mut options = pulldown_cmark::Options::empty();
+    options.insert(pulldown_cmark::Options::ENABLE_TABLES);
+    options.insert(pulldown_cmark::Options::ENABLE_FOOTNOTES);
+    options.insert(pulldown_cmark::Options::ENABLE_STRIKETHROUGH);
+    options.insert(pulldown_cmark::Options::ENABLE_TASKLISTS);
+    options.insert(pulldown_cmark::Options::ENABLE_SMART_PUNCTUATION);
+    options.insert(pulldown_cmark::Options::ENABLE_HEADING_ATTRIBUTES);
+    options.insert(pulldown_cmark::Options::ENABLE_GFM);
+    options.insert(pulldown_cmark::Options::ENABLE_MATH);
+    options
 }
 
-fn highlight_code(lang: &str, code: &str) -> String {
-    let ss = get_syntax_set();
-    let ts = get_theme_set();
-    let theme = &ts.themes["base16-ocean.dark"];
-
-    // 言語を検索、見つからなければPlainTextにフォールバック
-    let syntax = ss
-        .find_syntax_by_token(lang)
-        .unwrap_or_else(|| ss.find_syntax_plain_text());
-
-    // 言語表示名（空の場合は"text"）
-    let display_lang = if lang.is_empty() { "text" } else { lang };
-
-    // コードをdata属性用にエスケープ
-    let escaped_code = code
-        .replace('&', "&amp;")
-        .replace('"', "&quot;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;");
-
-    let highlighted_html = match highlighted_html_for_string(code, ss, syntax, theme) {
-        Ok(html) => html,
-        Err(_) => {
-            // エラー時はエスケープしてそのまま表示
-            format!(
-                "<pre><code>{}</code></pre>",
-                code.replace('&', "&amp;")
-                    .replace('<', "&lt;")
-                    .replace('>', "&gt;")
-            )
-        }
-    };
+/// 本文中の内部リンク・画像の参照先を行番号付きで集める（shortcodeの引数も含む）
+///
+/// 検証は全記事のパース後に行う。shortcodeを解析できなければ `(行番号, 説明)` を返す
+pub fn collect_links(
+    markdown_content: &str,
+    body_line: usize,
+) -> Result<Vec<InternalLink>, (usize, String)> {
+    let line_at = |offset: usize| body_line + markdown_content[..offset].matches('\n').count();
+    let mut internal_links = Vec::new();
+    let mut in_code_block = false;
 
-    // ヘッダーバー付きのコードブロックを生成
-    format!(
-        r#"<div class="code-block-wrapper">
-<div class="code-block-header">
-<span class="code-lang">{}</span>
-<button class="code-copy-btn" data-code="{}" title="コピー">
-<svg width="16" height="16" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
-<rect x="9" y="9" width="13" height="13" rx="2" ry="2"></rect>
-<path d="M5 15H4a2 2 0 0 1-2-2V4a2 2 0 0 1 2-2h9a2 2 0 0 1 2 2v1"></path>
-</svg>
-<span class="copy-text">Copy</span>
-</button>
-</div>
-{}</div>"#,
-        display_lang, escaped_code, highlighted_html
-    )
+    let parser = Parser::new_ext(markdown_content, markdown_options());
+    for (event, range) in TextMergeWithOffset::new(parser.into_offset_iter()) {
+        match &event {
+            Event::Start(Tag::Link { dest_url, .. } | Tag::Image { dest_url, .. })
+                if links::is_internal_candidate(dest_url) =>
+            {
+                internal_links.push(InternalLink {
+                    url: dest_url.to_string(),
+                    line: line_at(range.start),
+                });
+            }
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(TagEnd::CodeBlock) => in_code_block = false,
+            // コードブロック内は展開しないのでリンクにも数えない
+            Event::Text(text) if !in_code_block && text.contains("{{") => {
+                let mut collected = shortcodes::Collected::links_only();
+                let segments = shortcodes::expand(&markdown_content[range.clone()], &mut collected)
+                    .map_err(|e| (line_at(range.start + e.offset), e.to_string()))?;
+                if segments
+                    .iter()
+                    .any(|segment| matches!(segment, shortcodes::Segment::Html { .. }))
+                {
+                    let line = line_at(range.start);
+                    internal_links.extend(
+                        collected
+                            .links
+                            .into_iter()
+                            .filter(|url| links::is_internal_candidate(url))
+                            .map(|url| InternalLink { url, line }),
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(internal_links)
 }
 
-fn parse_markdown_file(input_path: &Path, dist_dir: &Path) -> anyhow::Result<Article> {
+fn parse_markdown_file(
+    input_path: &Path,
+    dist_dir: &Path,
+    build_cache: &BuildCache,
+) -> anyhow::Result<Article> {
     let markdown_with_metadata = fs::read_to_string(input_path)?;
 
-    let mut matter = Matter::<gray_matter::engine::TOML>::new();
-    matter.delimiter = "+++".to_string();
-    matter.close_delimiter = Some("+++".to_string());
-    let parsed_matter: ParsedEntity<MetaData> =
-        matter.parse::<MetaData>(&markdown_with_metadata)?;
+    // `dnfolio check` と同じ `file:line` 形式で報告する
+    let parsed_matter = front_matter::parse(&markdown_with_metadata).map_err(|e| {
+        let (line, message) = front_matter::error_location(&markdown_with_metadata, &e);
+        anyhow::anyhow!(
+            "{}:{line}: front matterを解析できません: {message}",
+            input_path.display()
+        )
+    })?;
 
-    let metadata: Option<MetaData> = parsed_matter.data;
+    let metadata: Option<MetaData> = parsed_matter.metadata;
 
     if let Some(meta) = &metadata {
         println!("Meta Data for {input_path:?}: Title = {}", meta.title);
-
-        if let Some(true) = meta.draft {
-            println!("Article {input_path:?} is not draft. Skipping HTML generation.");
-            return Err(anyhow::Error::msg("Draft article skipped"));
-        }
     } else {
-        println!("No metadata found for {input_path:?}.");
         eprintln!(
-            "DEBUG: Failed to parse metadata for {input_path:?}. Raw matter content (if any): {:?}",
-            parsed_matter.matter
+            "{}:1: warning: front matter ({1} ... {1}) がありません",
+            input_path.display(),
+            front_matter::DELIMITER
         );
     }
 
-    let markdown_content = parsed_matter.content;
+    let markdown_content = parsed_matter.body;
+    let body_line = parsed_matter.body_line;
 
-    let mut pulldown_options = pulldown_cmark::Options::empty();
-    pulldown_options.insert(pulldown_cmark::Options::ENABLE_TABLES);
-    pulldown_options.insert(pulldown_cmark::Options::ENABLE_FOOTNOTES);
-    pulldown_options.insert(pulldown_cmark::Options::ENABLE_STRIKETHROUGH);
-    pulldown_options.insert(pulldown_cmark::Options::ENABLE_TASKLISTS);
-    pulldown_options.insert(pulldown_cmark::Options::ENABLE_SMART_PUNCTUATION);
-    pulldown_options.insert(pulldown_cmark::Options::ENABLE_HEADING_ATTRIBUTES);
+    let links = collect_links(&markdown_content, body_line)
+        .map_err(|(line, message)| anyhow::anyhow!("{}:{line}: {message}", input_path.display()))?;
 
-    let parser = Parser::new_ext(&markdown_content, pulldown_options);
+    let parser = Parser::new_ext(&markdown_content, markdown_options());
     let mut headings: Vec<Heading> = Vec::new();
     let mut id_counts: HashMap<String, usize> = HashMap::new();
     let mut html_output = String::new();
@@ -236,16 +285,33 @@ fn parse_markdown_file(input_path: &Path, dist_dir: &Path) -> anyhow::Result<Art
 
     let mut in_code_block = false;
     let mut code_block_lang = String::new();
+    let mut code_block_line = 0;
     let mut code_block_content = String::new();
 
-    for event in parser {
+    let mut responsive_images: Vec<ResponsiveImage> = Vec::new();
+    let mut current_image: Option<(ResponsiveImage, String, String)> = None;
+    let mut unwrap_shortcode_paragraph = false;
+    let mut reading_stats = reading::ReadingStats::default();
+
+    // shortcodeが途中で分割されないよう連続するテキストをまとめる
+    for (event, range) in TextMergeWithOffset::new(parser.into_offset_iter()) {
+        // 読了時間用の文字数・単語数（コードブロックは除く）
+        if let Event::Text(text) | Event::Code(text) = &event
+            && !in_code_block
+        {
+            reading_stats.add(text);
+        }
+
         // ブロック要素のテキスト収集
         match &event {
-            Event::Text(text) | Event::Code(text) => {
-                if in_block {
-                    current_block_text.push_str(text);
-                    current_block_text.push(' ');
-                }
+            Event::Text(text)
+            | Event::Code(text)
+            | Event::InlineMath(text)
+            | Event::DisplayMath(text)
+                if in_block =>
+            {
+                current_block_text.push_str(text);
+                current_block_text.push(' ');
             }
             _ => {}
         }
@@ -291,6 +357,109 @@ fn parse_markdown_file(input_path: &Path, dist_dir: &Path) -> anyhow::Result<Art
             _ => {}
         }
 
+        // レスポンシブ画像: 代替テキストを集めてEnd(Image)で<picture>に置き換える
+        if let Some((image, title, alt)) = &mut current_image {
+            match event {
+                Event::Text(text) | Event::Code(text) => alt.push_str(&text),
+                Event::End(TagEnd::Image) => {
+                    let picture = images::picture_html(image, alt, title);
+                    processed_events.push(Event::Html(CowStr::from(picture)));
+                    responsive_images.push(image.clone());
+                    current_image = None;
+                }
+                _ => {}
+            }
+            continue;
+        }
+        if let Event::Start(Tag::Image {
+            dest_url, title, ..
+        }) = &event
+            && let Some(image) = images::inspect(Path::new("static"), dest_url, build_cache)
+        {
+            current_image = Some((image, title.to_string(), String::new()));
+            continue;
+        }
+
+        // ブロック要素のshortcodeで置き換えた段落は閉じタグも出力しない
+        if unwrap_shortcode_paragraph {
+            if event == Event::End(TagEnd::Paragraph) {
+                unwrap_shortcode_paragraph = false;
+                continue;
+            }
+            anyhow::bail!(
+                "{}:{}: ブロック要素のshortcodeは単独の段落に書いてください",
+                input_path.display(),
+                body_line + markdown_content[..range.start].matches('\n').count()
+            );
+        }
+
+        // shortcodeの展開（コードブロック・インラインコード内は対象外）
+        if let Event::Text(text) = &event
+            && !in_code_block
+            && text.contains("{{")
+        {
+            let line = body_line + markdown_content[..range.start].matches('\n').count();
+            // smart punctuationで引用符が置き換わる前の元テキストから解析する
+            let source_text = &markdown_content[range.clone()];
+            let mut collected = shortcodes::Collected::new(build_cache);
+            let segments = shortcodes::expand(source_text, &mut collected).map_err(|e| {
+                let line = body_line
+                    + markdown_content[..range.start + e.offset]
+                        .matches('\n')
+                        .count();
+                anyhow::anyhow!("{}:{line}: {e}", input_path.display())
+            })?;
+
+            if segments
+                .iter()
+                .any(|segment| matches!(segment, shortcodes::Segment::Html { .. }))
+            {
+                responsive_images.extend(collected.images);
+
+                let has_block = segments.iter().any(|segment| {
+                    matches!(segment, shortcodes::Segment::Html { block: true, .. })
+                });
+                let is_alone = segments
+                    .iter()
+                    .filter(|segment| match segment {
+                        shortcodes::Segment::Text(text) => !text.trim().is_empty(),
+                        shortcodes::Segment::Html { .. } => true,
+                    })
+                    .count()
+                    == 1;
+                if has_block && !is_alone {
+                    anyhow::bail!(
+                        "{}:{line}: ブロック要素のshortcodeは単独の段落に書いてください",
+                        input_path.display()
+                    );
+                }
+                if has_block && processed_events.last() == Some(&Event::Start(Tag::Paragraph)) {
+                    processed_events.pop();
+                    unwrap_shortcode_paragraph = true;
+                }
+
+                for segment in segments {
+                    match segment {
+                        shortcodes::Segment::Text(text) if !has_block => {
+                            let text = shortcodes::unescape_markdown(text);
+                            if is_in_heading {
+                                current_heading_text_buffer.push_str(&text);
+                            }
+                            processed_events.push(Event::Text(CowStr::from(text)));
+                        }
+                        shortcodes::Segment::Text(_) => {}
+                        shortcodes::Segment::Html { html, block: true } => {
+                            processed_events.push(Event::Html(CowStr::from(html)));
+                        }
+                        shortcodes::Segment::Html { html, block: false } => {
+                            processed_events.push(Event::InlineHtml(CowStr::from(html)));
+                        }
+                    }
+                }
+                continue;
+            }
+        }
+
         match event {
             Event::Start(Tag::Heading {
                 level,
@@ -408,23 +577,51 @@ fn parse_markdown_file(input_path: &Path, dist_dir: &Path) -> anyhow::Result<Art
                 }
                 processed_events.push(Event::Code(text));
             }
+            Event::InlineMath(ref source) | Event::DisplayMath(ref source) => {
+                let display = matches!(event, Event::DisplayMath(_));
+                let (mathml, errors) = math::to_mathml(source, display);
+                let line = body_line + markdown_content[..range.start].matches('\n').count();
+                for error in errors {
+                    eprintln!("{}:{line}: warning: {error}", input_path.display());
+                }
+                if is_in_heading {
+                    current_heading_text_buffer.push_str(source);
+                }
+                processed_events.push(if display {
+                    Event::Html(CowStr::from(mathml))
+                } else {
+                    Event::InlineHtml(CowStr::from(mathml))
+                });
+            }
+            Event::Start(Tag::BlockQuote(Some(kind))) => {
+                processed_events.push(Event::Start(Tag::BlockQuote(Some(kind))));
+                processed_events.push(Event::Html(CowStr::from(alert_title_html(kind))));
+            }
             Event::Start(Tag::CodeBlock(kind)) => {
                 in_code_block = true;
                 code_block_lang = match kind {
                     pulldown_cmark::CodeBlockKind::Fenced(lang) => lang.to_string(),
                     pulldown_cmark::CodeBlockKind::Indented => String::new(),
                 };
+                code_block_line = body_line + markdown_content[..range.start].matches('\n').count();
                 code_block_content.clear();
             }
             Event::End(TagEnd::CodeBlock) => {
                 in_code_block = false;
-                let highlighted = highlight_code(&code_block_lang, &code_block_content);
+                let (fence, warnings) = highlight::CodeFence::parse(&code_block_lang);
+                for warning in warnings {
+                    eprintln!(
+                        "{}:{code_block_line}: warning: {warning}",
+                        input_path.display()
+                    );
+                }
+                let highlighted = highlight::highlight_code(&fence, &code_block_content);
                 processed_events.push(Event::Html(CowStr::from(highlighted)));
             }
             // セキュリティ: MarkdownインラインHTMLをブロック（XSS対策）
             // pulldown-cmarkはデフォルトでHTMLをパススルーするため、
             // <script>や<iframe>等の危険なタグがそのまま出力されるのを防ぐ。
-            // highlight_code()等で生成した信頼済みEvent::Htmlは
+            // highlight::highlight_code()等で生成した信頼済みEvent::Htmlは
             // この分岐に到達する前にprocessed_eventsへpush済み。
             Event::Html(html) | Event::InlineHtml(html) => {
                 // HTMLタグをテキストとして出力（自動エスケープされる）
@@ -459,19 +656,7 @@ fn parse_markdown_file(input_path: &Path, dist_dir: &Path) -> anyhow::Result<Art
     // println!("\n--- HTML Output with IDs for {input_path:?} ---\n{html_output}");
     // println!("\n=============================================================\n");
 
-    let file_stem = input_path.file_stem().unwrap().to_string_lossy();
-    let article_slug = metadata
-        .as_ref()
-        .and_then(|m| m.slug.as_ref())
-        .map(|s| s.to_string())
-        .unwrap_or_else(|| {
-            let name_part = file_stem.split('_').skip(1).collect::<Vec<_>>().join("-");
-            if name_part.is_empty() {
-                slugify(&file_stem)
-            } else {
-                slugify(&name_part)
-            }
-        });
+    let article_slug = article_slug(input_path, metadata.as_ref());
 
     // dist/posts/{slug}/index.html と出力される
     let output_path = dist_dir
@@ -488,9 +673,36 @@ fn parse_markdown_file(input_path: &Path, dist_dir: &Path) -> anyhow::Result<Art
         relative_url,
         table_of_contents_html,
         source_path: input_path.to_path_buf(),
+        headings,
+        links,
+        images: responsive_images,
+        reading: reading_stats,
+        revisions: Vec::new(),
     })
 }
 
+/// キャッシュ済みならそれを返し、なければパースしてキャッシュに保存する
+fn load_or_parse_article(
+    input_path: &Path,
+    dist_dir: &Path,
+    build_cache: &BuildCache,
+) -> anyhow::Result<Article> {
+    let source = fs::read(input_path)?;
+    let key = build_cache.article_key(input_path, &source, dist_dir);
+    // 参照している画像の寸法や縮小版が変わっていれば<picture>を作り直す
+    if let Some(article) = build_cache.load_article(&key)
+        && article.images.iter().all(|image| {
+            images::inspect(Path::new("static"), &image.url, build_cache).as_ref() == Some(image)
+        })
+    {
+        return Ok(article);
+    }
+
+    let article = parse_markdown_file(input_path, dist_dir, build_cache)?;
+    build_cache.store_article(&key, &article)?;
+    Ok(article)
+}
+
 fn parse_page_file(input_path: &Path, _pages_dir: &Path, dist_dir: &Path) -> anyhow::Result<Page> {
     let markdown_content = fs::read_to_string(input_path)?;
 
@@ -501,12 +713,18 @@ fn parse_page_file(input_path: &Path, _pages_dir: &Path, dist_dir: &Path) -> any
     pulldown_options.insert(pulldown_cmark::Options::ENABLE_TASKLISTS);
     pulldown_options.insert(pulldown_cmark::Options::ENABLE_SMART_PUNCTUATION);
     pulldown_options.insert(pulldown_cmark::Options::ENABLE_HEADING_ATTRIBUTES);
+    pulldown_options.insert(pulldown_cmark::Options::ENABLE_GFM);
 
     // セキュリティ: MarkdownインラインHTMLをブロック（XSS対策）
-    let parser = Parser::new_ext(&markdown_content, pulldown_options).map(|event| match event {
-        Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
-        other => other,
-    });
+    let parser =
+        Parser::new_ext(&markdown_content, pulldown_options).flat_map(|event| match event {
+            Event::Html(html) | Event::InlineHtml(html) => vec![Event::Text(html)],
+            Event::Start(Tag::BlockQuote(Some(kind))) => vec![
+                Event::Start(Tag::BlockQuote(Some(kind))),
+                Event::Html(CowStr::from(alert_title_html(kind))),
+            ],
+            other => vec![other],
+        });
     let mut html_content = String::new();
     pulldown_cmark::html::push_html(&mut html_content, parser);
 
@@ -523,19 +741,13 @@ fn parse_page_file(input_path: &Path, _pages_dir: &Path, dist_dir: &Path) -> any
         output_path,
         relative_url,
         filename: file_stem,
+        last_modified: None,
     })
 }
 
-fn language_display_name(language: &str) -> &str {
-    match language {
-        "en" => "English",
-        "ja" => "日本語",
-        _ => language,
-    }
-}
-
-fn collect_tags(articles: &[Article]) -> HashMap<String, TagInfo> {
-    let mut tag_map: HashMap<String, TagInfo> = HashMap::new();
+/// タグごとの記事（出力が実行ごとに変わらないようタグ名順）
+fn collect_tags(articles: &[Article]) -> BTreeMap<String, TagInfo> {
+    let mut tag_map: BTreeMap<String, TagInfo> = BTreeMap::new();
 
     for article in articles {
         if let Some(metadata) = &article.metadata
@@ -556,85 +768,173 @@ fn collect_tags(articles: &[Article]) -> HashMap<String, TagInfo> {
     tag_map
 }
 
-fn generate_tag_pages(
-    tag_map: &HashMap<String, TagInfo>,
-    dist_dir: &Path,
-    articles_list_markup: &Markup,
-) -> Result<()> {
-    let tags_dir = dist_dir.join("tags");
-    fs::create_dir_all(&tags_dir)?;
-
-    for (tag_name, tag_info) in tag_map {
-        let tag_slug = slugify(tag_name);
-        let tag_page_dir = tags_dir.join(&tag_slug);
-        fs::create_dir_all(&tag_page_dir)?;
-        let tag_page_path = tag_page_dir.join("index.html");
-
-        let tag_main_content_markup = html! {
-            h1 { "タグ: " (tag_name) "(" (tag_info.count) "件)" }
-            ul {
-                @for article in &tag_info.articles {
-                    li {
-                        a href=(article.relative_url.to_string_lossy().to_string()) {
-                            @if let Some(meta) = &article.metadata {
-                                (meta.title)
-                            } @else {
-                                (article.output_path.file_name().unwrap_or_default().to_string_lossy())
-                            }
-                        }
-                        @if let Some(meta) = &article.metadata &&  let Some(ref taxonomies) = meta.taxonomies && let Some(ref tags) = taxonomies.tags {
-                            " - "
-                                @for (i, tag) in tags.iter().enumerate() {
-                                    @if i > 0 { ", " }
-                                    span style="font-size: 0.9em; color: #666;" { (tag) }
-                                }
+/// アーカイブページの記事一覧（タイトルと説明）
+fn archive_list_markup(articles: &[Article]) -> Markup {
+    html! {
+        ul class="archive-list" {
+            @for article in articles {
+                li {
+                    a href=(article.relative_url.to_string_lossy().to_string()) {
+                        @if let Some(meta) = &article.metadata {
+                            (meta.title)
+                        } @else {
+                            (article.output_path.file_name().unwrap_or_default().to_string_lossy())
                         }
                     }
+                    @if let Some(meta) = &article.metadata && let Some(ref description) = meta.description {
+                        p class="archive-description" { (description) }
+                    }
                 }
             }
-        };
+        }
+    }
+}
 
-        let tag_sidebar_right_markup = html! {
-            h2 { "サイト情報" }
-            ul {
-                li {
-                    a href="/" { "ホームに戻る" }
-                }
+/// 年別・月別アーカイブページ（`/posts/2025/`, `/posts/2025/01/`）を生成する
+fn generate_archive_pages(
+    site: &SiteConfig,
+    year_groups: &[YearGroup],
+    dist_dir: &Path,
+    articles_list_markup: &Markup,
+    ogp_renderer: &ogp::OgpRenderer,
+) -> Result<()> {
+    let sidebar_right_markup = html! {
+        h2 { "サイト情報" }
+        ul {
+            li {
+                a href="/" { "ホームに戻る" }
             }
-        };
+        }
+    };
+
+    let write_page = |url: &str,
+                      title: &str,
+                      parent: Option<(&str, &str)>,
+                      count: usize,
+                      main_content_markup: Markup|
+     -> Result<()> {
+        let page_dir = dist_dir.join(url.trim_matches('/'));
+        fs::create_dir_all(&page_dir)?;
 
-        let tag_canonical_url = format!("https://dnfolio.me/tags/{tag_slug}/");
+        let ogp_image_path =
+            ogp_renderer.render_ogp_png(url, &ogp::OgpCard::Archive { title, count })?;
 
-        let tag_url = format!("/tags/{}/", tag_slug);
         let structured_data = structured_data::generate_structured_data_html(
-            structured_data::PageType::TagPage {
-                tag_name,
-                url: &tag_url,
-            },
+            site,
+            structured_data::PageType::ArchivePage { title, url, parent },
             None,
         );
 
-        let tag_html_output = base::layout(
+        let html_output = base::layout(
             PageConfig {
-                page_title: &format!("タグ: {tag_name}"),
-                canonical_url: &tag_canonical_url,
+                site,
+                page_title: &format!("{title}の記事"),
+                canonical_url: &site.absolute_url(url),
                 metadata: None,
-                ogp_image_path: None,
+                ogp_image_path: Some(&ogp_image_path),
                 structured_data_html: Some(&structured_data),
-                robots_directive: Some("noindex,follow"),
+                robots_directive: None,
                 article_dates: None,
+                extra_feed: None,
+                language: None,
+                translations: None,
             },
             articles_list_markup.clone(),
-            tag_main_content_markup,
-            tag_sidebar_right_markup,
+            main_content_markup,
+            sidebar_right_markup.clone(),
         )
         .into_string();
-        fs::write(tag_page_path, tag_html_output)?;
+        fs::write(page_dir.join("index.html"), html_output)?;
+        Ok(())
+    };
+
+    for year_group in year_groups {
+        let year_url = year_group.url();
+        let year_title = format!("{}年", year_group.year);
+
+        let year_main_content_markup = html! {
+            h1 { (year_title) "の記事(" (year_group.article_count()) "件)" }
+            @for month_group in &year_group.months {
+                h2 {
+                    a href=(month_group.url(year_group.year)) {
+                        (format!("{}年{:02}月", year_group.year, month_group.month))
+                    }
+                    "(" (month_group.articles.len()) "件)"
+                }
+                (archive_list_markup(&month_group.articles))
+            }
+        };
+        write_page(
+            &year_url,
+            &year_title,
+            None,
+            year_group.article_count(),
+            year_main_content_markup,
+        )?;
+
+        for month_group in &year_group.months {
+            let month_title = format!("{}年{:02}月", year_group.year, month_group.month);
+            let month_main_content_markup = html! {
+                h1 { (month_title) "の記事(" (month_group.articles.len()) "件)" }
+                (archive_list_markup(&month_group.articles))
+            };
+            write_page(
+                &month_group.url(year_group.year),
+                &month_title,
+                Some((&year_title, &year_url)),
+                month_group.articles.len(),
+                month_main_content_markup,
+            )?;
+        }
     }
     Ok(())
 }
 
-pub async fn run() -> Result<()> {
+/// アーカイブページのURLとlastmod（含まれる記事の最終更新日）
+fn archive_sitemap_entries(year_groups: &[YearGroup]) -> Vec<sitemap::SitemapEntry> {
+    let mut entries = Vec::new();
+    for year_group in year_groups {
+        let month_entries: Vec<sitemap::SitemapEntry> = year_group
+            .months
+            .iter()
+            .filter_map(|month_group| {
+                Some(sitemap::SitemapEntry {
+                    url: month_group.url(year_group.year),
+                    lastmod: dates::latest_article_lastmod(&month_group.articles)?,
+                })
+            })
+            .collect();
+        if let Some(lastmod) = month_entries.iter().map(|entry| entry.lastmod).max() {
+            entries.push(sitemap::SitemapEntry {
+                url: year_group.url(),
+                lastmod,
+            });
+        }
+        entries.extend(month_entries);
+    }
+    entries
+}
+
+/// ビルド時のオプション（CLIフラグから生成）
+#[derive(Clone)]
+pub struct BuildOptions {
+    /// `.dnfolio-cache/` を使ったインクリメンタルビルド
+    pub use_cache: bool,
+    /// リンク切れがあればビルドを失敗させる
+    pub strict: bool,
+    /// 下書き・予約投稿をプレビューとして出力する（`serve --drafts`）
+    pub drafts: bool,
+    /// 予約投稿の判定に使う現在時刻（未指定ならビルド時の時刻）
+    pub now: Option<DateTime<FixedOffset>>,
+}
+
+pub async fn run(site: &SiteConfig, options: &BuildOptions) -> Result<()> {
+    let build_cache = if options.use_cache {
+        BuildCache::open(Path::new(cache::CACHE_DIR))?
+    } else {
+        BuildCache::disabled()
+    };
+
     let content_dir = PathBuf::from("content");
     let dist_dir = Path::new("dist");
     let ogp_dir = dist_dir.join("ogp");
@@ -645,9 +945,11 @@ pub async fn run() -> Result<()> {
     if dist_dir.exists() {
         // SSGが生成するディレクトリを削除
         let dirs_to_clean = [
-            "posts", "tags", "ogp", "about", "privacy", "content", "icons", "sns",
+            "posts", "tags", "series", "ogp", "about", "privacy", "content", "icons", "sns",
         ];
-        for dir_name in dirs_to_clean {
+        // 言語別の記事一覧・フィード・404ページ（`en/` など）
+        let language_dirs = i18n::LANGUAGES.map(|(code, _)| code);
+        for dir_name in dirs_to_clean.into_iter().chain(language_dirs) {
             let dir_path = dist_dir.join(dir_name);
             if dir_path.exists() {
                 fs::remove_dir_all(&dir_path)?;
@@ -678,9 +980,15 @@ pub async fn run() -> Result<()> {
             fs::copy(entry.path(), &target_path)?;
         }
     }
-    fs::write(dist_dir.join("app.css"), base::minified_stylesheet())?;
+    fs::write(
+        dist_dir.join("app.css"),
+        base::minified_stylesheet() + &highlight::stylesheet()?,
+    )?;
+    fs::write(dist_dir.join("robots.txt"), site.robots_txt())?;
 
+    // 同じ日付の記事の並び順がファイルシステムに依存しないようファイル名順に読む
     let markdown_files: Vec<PathBuf> = WalkDir::new(&content_dir)
+        .sort_by_file_name()
         .into_iter()
         .filter_map(|entry_result| {
             let entry = entry_result.ok()?;
@@ -693,19 +1001,21 @@ pub async fn run() -> Result<()> {
         })
         .collect();
 
+    // 記事・固定ページのコミット履歴（更新日のフォールバックと更新履歴に使う）
+    let history = history::load(&[&content_dir, &pages_dir]);
+
     let mut articles: Vec<Article> = markdown_files
         .par_iter()
         .filter_map(|input_path| {
             println!("Parsing {input_path:?}");
 
-            match parse_markdown_file(input_path, dist_dir) {
-                Ok(article) => Some(article),
+            match load_or_parse_article(input_path, dist_dir, &build_cache) {
+                Ok(mut article) => {
+                    article.revisions = history.get(input_path).cloned().unwrap_or_default();
+                    Some(article)
+                }
                 Err(e) => {
-                    if e.to_string().contains("Draft article skipped") {
-                        println!("Skipped draft article: {input_path:?}");
-                    } else {
-                        eprintln!("Error processing {input_path:?}: {e}");
-                    }
+                    eprintln!("Error processing {input_path:?}: {e}");
                     None
                 }
             }
@@ -728,6 +1038,36 @@ pub async fn run() -> Result<()> {
         }
     });
 
+    // 下書き・予約投稿は一覧・検索・サイトマップ・フィードに載せない
+    // （`--drafts` のときは記事ページだけプレビューとして出力する）
+    // `SOURCE_DATE_EPOCH` があればその時刻で判定し、出力が実行時刻に左右されないようにする
+    let now = options.now.unwrap_or_else(dates::build_time);
+    let (articles, unpublished): (Vec<Article>, Vec<Article>) = articles
+        .into_iter()
+        .partition(|article| publish::visibility(article.metadata.as_ref(), now).is_published());
+    let mut previews: HashMap<PathBuf, publish::Visibility> = HashMap::new();
+    let mut preview_articles: Vec<Article> = Vec::new();
+    for article in unpublished {
+        let visibility = publish::visibility(article.metadata.as_ref(), now);
+        if options.drafts {
+            println!(
+                "Draft preview: {} ({})",
+                article.relative_url.display(),
+                visibility.banner_label()
+            );
+            previews.insert(article.relative_url.clone(), visibility);
+            preview_articles.push(article);
+        } else if let publish::Visibility::Scheduled(publish_at) = visibility {
+            println!(
+                "Skipped scheduled article until {}: {:?}",
+                publish_at.to_rfc3339(),
+                article.source_path
+            );
+        } else {
+            println!("Skipped draft article: {:?}", article.source_path);
+        }
+    }
+
     // grep風検索用のインデックス生成（行単位）
     #[derive(serde::Serialize)]
     struct SearchLine {
@@ -818,6 +1158,7 @@ pub async fn run() -> Result<()> {
     fs::write(dist_dir.join("tags-index.json"), tags_index_json)?;
 
     let pages_files: Vec<PathBuf> = WalkDir::new(&pages_dir)
+        .sort_by_file_name()
         .into_iter()
         .filter_map(|entry_result| {
             let entry = entry_result.ok()?;
@@ -835,7 +1176,13 @@ pub async fn run() -> Result<()> {
         .filter_map(|input_path| {
             println!("Parsing page {input_path:?}");
             match parse_page_file(input_path, &pages_dir, dist_dir) {
-                Ok(page) => Some(page),
+                Ok(mut page) => {
+                    page.last_modified = history
+                        .get(input_path)
+                        .and_then(|revisions| revisions.first())
+                        .map(|revision| revision.date);
+                    Some(page)
+                }
                 Err(e) => {
                     eprintln!("Error processing page {input_path:?}: {e}");
                     None
@@ -844,30 +1191,107 @@ pub async fn run() -> Result<()> {
         })
         .collect();
 
+    // 記事内の内部リンクを検証（生成されるURL・見出しID・static/ と突き合わせる）
+    let mut generated_urls: Vec<String> = ["/", "/sitemap.xml", "/robots.txt"]
+        .into_iter()
+        .chain(feeds::FEED_PATHS)
+        .map(String::from)
+        .collect();
+    generated_urls.extend(
+        pages
+            .iter()
+            .map(|page| page.relative_url.to_string_lossy().into_owned()),
+    );
+    generated_urls.extend(tag_map.keys().map(|tag_name| tags::tag_url(tag_name)));
+    generated_urls.push("/tags/".to_string());
+    for language in i18n::group_by_language(site, &articles).keys() {
+        generated_urls.push(i18n::index_url(language));
+        generated_urls.push(i18n::feed_url(language));
+    }
+    let series_list = series::collect(&articles);
+    generated_urls.extend(series_list.iter().map(|series| series.url()));
+    // 記事を年月別にグループ化
+    let year_groups = group_articles_by_year_month(&articles);
+    let archive_entries = archive_sitemap_entries(&year_groups);
+    generated_urls.extend(archive_entries.iter().map(|entry| entry.url.clone()));
+    let broken_links = links::validate(site, &articles, &generated_urls, Path::new("static"));
+    for broken_link in &broken_links {
+        eprintln!("{broken_link}");
+    }
+    if !broken_links.is_empty() {
+        if options.strict {
+            anyhow::bail!("found {} broken links (strict mode)", broken_links.len());
+        }
+        println!("Found {} broken links", broken_links.len());
+    }
+
+    let article_images: Vec<&ResponsiveImage> = articles
+        .iter()
+        .chain(&preview_articles)
+        .flat_map(|article| &article.images)
+        .collect();
+    images::write_variants(&article_images, Path::new("static"), dist_dir, &build_cache)?;
+
     let about_content = pages
         .iter()
         .find(|page| page.filename == "about")
         .map(|page| maud::PreEscaped(page.content_html.clone()))
         .unwrap_or_else(|| maud::PreEscaped("About content not found".to_string()));
 
-    let articles_arc = Arc::new(articles.clone());
-
-    // 記事を年月別にグループ化
-    let year_groups = group_articles_by_year_month(&articles);
+    let articles_arc = Arc::new(
+        articles
+            .iter()
+            .chain(&preview_articles)
+            .cloned()
+            .collect::<Vec<_>>(),
+    );
+    let article_links = related::compute(&articles);
+    let translations = i18n::collect_translations(site, &articles);
 
     // デフォルトの記事一覧（ホームページ用、目次なし）
     let articles_list_markup: Markup = generate_file_tree_markup(&year_groups, None, None);
 
-    generate_tag_pages(&tag_map, dist_dir, &articles_list_markup)?;
-
-    let mut font_db = fontdb::Database::new();
-    font_db.load_font_file("assets/NotoSansJP-Regular.ttf")?;
-    font_db.load_font_file("assets/NotoSansJP-Bold.ttf")?;
-
-    let usvg_options = usvg::Options {
-        fontdb: std::sync::Arc::new(font_db),
-        ..Default::default()
-    };
+    // OGP画像（記事・一覧ページ・トップページなど全ページ共通のレンダラー）
+    let ogp_renderer = ogp::OgpRenderer::new(&ogp_dir, &build_cache)?;
+
+    let tag_meta = tags::load_tag_meta(Path::new(tags::TAGS_CONFIG_PATH))?;
+    tags::generate_tag_index_page(
+        site,
+        &sorted_tags,
+        &tag_meta,
+        dist_dir,
+        &articles_list_markup,
+        &ogp_renderer,
+    )?;
+    tags::generate_tag_pages(
+        site,
+        &tag_map,
+        &tag_meta,
+        dist_dir,
+        &articles_list_markup,
+        &ogp_renderer,
+    )?;
+    series::generate_series_pages(
+        site,
+        &series_list,
+        dist_dir,
+        &articles_list_markup,
+        &ogp_renderer,
+    )?;
+    generate_archive_pages(
+        site,
+        &year_groups,
+        dist_dir,
+        &articles_list_markup,
+        &ogp_renderer,
+    )?;
+    i18n::generate_language_indexes(
+        site,
+        &articles,
+        dist_dir,
+        &articles_list_markup,
+        &ogp_renderer,
+    )?;
 
     articles_arc
         .par_iter()
@@ -889,35 +1313,29 @@ pub async fn run() -> Result<()> {
                 .map(|m| m.title.as_str())
                 .unwrap_or("記事");
 
-            let ogp_svg_url_path = ogp::generate_ogp_svg(page_title, &ogp_dir).map_err(|e| anyhow::Error::msg(format!("OGP SVG generation failed: {e}")))?;
-            let ogp_svg_fs_path = dist_dir.join(ogp_svg_url_path.strip_prefix("/").unwrap_or(&ogp_svg_url_path));
-
-            let svg_data = fs::read(&ogp_svg_fs_path)?;
-            let tree = usvg::Tree::from_data(&svg_data, &usvg_options)?;
-            let pixmap_size = tree.size().to_int_size();
-            let mut pixmap = tiny_skia::Pixmap::new(pixmap_size.width(), pixmap_size.height()).ok_or_else(|| anyhow::Error::msg("Failed to create pixmap"))?;
-
-            resvg::render(&tree, tiny_skia::Transform::identity(), &mut pixmap.as_mut());
-
-            let ogp_png_url_path = ogp_svg_url_path.replace(".svg", ".png");
-            let ogp_png_fs_path = dist_dir.join(ogp_png_url_path.strip_prefix('/').unwrap_or(&ogp_png_url_path));
-
-            pixmap.save_png(&ogp_png_fs_path)?;
+            let ogp_image_path = ogp_renderer
+                .render_ogp_png(
+                    &article.relative_url.to_string_lossy(),
+                    &ogp::OgpCard::Article { title: page_title },
+                )
+                .map_err(|e| anyhow::Error::msg(format!("OGP image generation failed: {e}")))?;
 
-            let ogp_image_path = ogp_png_url_path;
-
-            let canonical_url = format!("https://dnfolio.me{}", article.relative_url.to_string_lossy());
+            let canonical_url = site.absolute_url(&article.relative_url.to_string_lossy());
             let article_dates = dates::resolve_article_dates(article).ok_or_else(|| {
-                anyhow::Error::msg(format!(
-                    "公開日を解決できません: {:?}",
-                    article.source_path
-                ))
+                anyhow::Error::msg(format!("公開日を解決できません: {:?}", article.source_path))
             })?;
             let published_time = article_dates.published.to_rfc3339();
             let modified_time = article_dates.modified.to_rfc3339();
+            let series_position = series::find(&series_list, article);
+
+            let preview = previews.get(&article.relative_url);
+            let strings = i18n::ui(i18n::article_language(site, article));
 
             // 新しいNeovim風のスタイルでメインコンテンツを生成
             let main_content_markup = html! {
+                @if let Some(visibility) = preview {
+                    div class="draft-banner" role="note" { (visibility.banner_label()) }
+                }
                 @if let Some(meta) = &article.metadata {
                     img src=(ogp_image_path) alt=(meta.title) decoding="async";
                 }
@@ -934,7 +1352,7 @@ pub async fn run() -> Result<()> {
                     && let Some(ref taxonomies) = meta.taxonomies
                     && let Some(ref languages) = taxonomies.languages {
                         @for language in languages {
-                            li { span class="badge badge-lang" { (language_display_name(language)) } }
+                            li { span class="badge badge-lang" { (i18n::display_name(language)) } }
                         }
                     }
                 }
@@ -947,37 +1365,83 @@ pub async fn run() -> Result<()> {
                             li { span class="badge badge-tag" { (tag) } }
                         }
                     }
+                    li {
+                        span class="badge badge-reading"
+                            title=(format!(
+                                "{}{} / {}{}",
+                                article.reading.characters,
+                                strings.reading_characters_suffix,
+                                article.reading.words,
+                                strings.reading_words_suffix,
+                            )) {
+                            (strings.reading_time_prefix) (article.reading.minutes()) (strings.reading_time_suffix)
+                        }
+                    }
+                }
+                // シリーズ（nav要素なので行番号には数えない）
+                @if let Some(position) = &series_position {
+                    (series::part_box_markup(position, strings))
                 }
                 (maud::PreEscaped(&article.content_html))
+                @if let Some(position) = &series_position {
+                    (series::navigation_markup(position, strings))
+                }
+                (history::markup(site, &article.revisions, strings))
+                @if let Some(links) = article_links.get(&article.relative_url) {
+                    (related::markup(links, strings))
+                }
             };
 
             // 現在の記事URL
             let article_url_str = article.relative_url.to_string_lossy().to_string();
 
             // 目次なしのサイドバーを生成（目次はconfig経由でトップに配置）
-            let article_sidebar_markup = generate_file_tree_markup(&year_groups, Some(&article_url_str), None);
+            let article_sidebar_markup =
+                generate_file_tree_markup(&year_groups, Some(&article_url_str), None);
 
             let article_url = article.relative_url.to_string_lossy();
+            let series_url = series_position
+                .as_ref()
+                .map(|position| position.series.url())
+                .unwrap_or_default();
             let structured_data = structured_data::generate_structured_data_html(
+                site,
                 structured_data::PageType::Article {
                     url: &article_url,
                     ogp_image_url: &ogp_image_path,
                     published_date: &published_time,
                     modified_date: &modified_time,
+                    series: series_position
+                        .as_ref()
+                        .map(|position| structured_data::SeriesPart {
+                            name: &position.series.name,
+                            url: &series_url,
+                            position: position.part(),
+                        }),
+                    reading: &article.reading,
                 },
                 article.metadata.as_ref(),
             );
 
+            let article_language = i18n::article_language(site, article);
+            let language_feed_title =
+                format!("{} ({})", site.title, i18n::display_name(article_language));
+            let language_feed_url = i18n::feed_url(article_language);
+
             let full_article_html = base::layout_with_toc(
                 ArticlePageConfig {
                     base: PageConfig {
+                        site,
                         page_title,
                         canonical_url: &canonical_url,
                         metadata: article.metadata.as_ref(),
                         ogp_image_path: Some(&ogp_image_path),
                         structured_data_html: Some(&structured_data),
-                        robots_directive: None,
+                        robots_directive: preview.map(|_| "noindex,nofollow"),
                         article_dates: Some((&published_time, &modified_time)),
+                        extra_feed: Some((&language_feed_title, &language_feed_url)),
+                        language: Some(article_language),
+                        translations: i18n::article_translations(&translations, article),
                     },
                     toc_html: Some(&article.table_of_contents_html),
                 },
@@ -1010,29 +1474,39 @@ pub async fn run() -> Result<()> {
             @for tag_info in &sorted_tags {
                 li {
                     a href=(format!("/tags/{}/", slugify(&tag_info.name))) {
-                        (tag_info.name) " " span style="color: #666;" { "(" (tag_info.count) ")" }
+                        (tag_info.name) span class="tag-index-count" { "(" (tag_info.count) ")" }
                     }
                 }
             }
         }
     };
 
-    let index_ogp_path = ogp::generate_ogp_svg("dnfolio", &ogp_dir)?;
+    let index_ogp_path = ogp_renderer.render_ogp_png(
+        "/",
+        &ogp::OgpCard::Home {
+            title: &site.title,
+            description: &site.description,
+        },
+    )?;
 
-    let index_canonical_url: &str = "https://dnfolio.me/";
+    let index_canonical_url = site.home_url();
 
     let home_structured_data =
-        structured_data::generate_structured_data_html(structured_data::PageType::Home, None);
+        structured_data::generate_structured_data_html(site, structured_data::PageType::Home, None);
 
     let index_html_output = base::layout(
         PageConfig {
-            page_title: "dnfolio",
-            canonical_url: index_canonical_url,
+            site,
+            page_title: &site.title,
+            canonical_url: &index_canonical_url,
             metadata: None,
             ogp_image_path: Some(&index_ogp_path),
             structured_data_html: Some(&home_structured_data),
             robots_directive: None,
             article_dates: None,
+            extra_feed: None,
+            language: None,
+            translations: None,
         },
         articles_list_markup.clone(),
         index_main_content_markup,
@@ -1059,20 +1533,27 @@ pub async fn run() -> Result<()> {
             }
         };
 
-        let privacy_canonical_url = format!(
-            "https://dnfolio.me{}",
-            privacy_page.relative_url.to_string_lossy()
-        );
+        let privacy_canonical_url = site.absolute_url(&privacy_page.relative_url.to_string_lossy());
+        let privacy_ogp_path = ogp_renderer.render_ogp_png(
+            &privacy_page.relative_url.to_string_lossy(),
+            &ogp::OgpCard::Article {
+                title: "プライバシーポリシー",
+            },
+        )?;
 
         let privacy_html_output = base::layout(
             PageConfig {
+                site,
                 page_title: "プライバシーポリシー",
                 canonical_url: &privacy_canonical_url,
                 metadata: None,
-                ogp_image_path: None,
+                ogp_image_path: Some(&privacy_ogp_path),
                 structured_data_html: None,
                 robots_directive: None,
                 article_dates: None,
+                extra_feed: None,
+                language: None,
+                translations: None,
             },
             articles_list_markup.clone(),
             privacy_main_content_markup,
@@ -1090,50 +1571,19 @@ pub async fn run() -> Result<()> {
         )?;
     }
 
-    // 404ページを生成
-    let not_found_main_content = html! {
-        div style="text-align: center; padding: 4rem 1rem;" {
-            h1 style="font-size: 4rem; margin-bottom: 1rem; color: #6c757d;" { "404" }
-            p style="font-size: 1.25rem; color: #6c757d; margin-bottom: 2rem;" {
-                "お探しのページは見つかりませんでした。"
-            }
-            a href="/" style="color: #007bff; text-decoration: none;" { "ホームに戻る" }
-        }
-    };
-
-    let not_found_sidebar_right = html! {
-        h2 { "サイト情報" }
-        ul {
-            li {
-                a href="/" { "ホームに戻る" }
-            }
-        }
-    };
-
-    let not_found_html = base::layout(
-        PageConfig {
-            page_title: "ページが見つかりません - dnfolio",
-            canonical_url: "https://dnfolio.me/404",
-            metadata: None,
-            ogp_image_path: Some("/ogp/dnfolio.png"),
-            structured_data_html: None,
-            robots_directive: Some("noindex,follow"),
-            article_dates: None,
-        },
-        articles_list_markup.clone(),
-        not_found_main_content,
-        not_found_sidebar_right,
-    )
-    .into_string();
+    // 404ページを生成（言語ごと）
+    i18n::generate_not_found_pages(site, dist_dir, &articles_list_markup, &ogp_renderer)?;
 
-    fs::write(dist_dir.join("404.html"), not_found_html)?;
-    println!("Generated 404.html");
+    sitemap::generate_and_write_sitemap(site, &articles, &pages, &archive_entries, dist_dir)?;
 
-    let base_url = "https://dnfolio.me";
-    sitemap::generate_and_write_sitemap(base_url, &articles, &pages, dist_dir)?;
-
-    rss::generate_rss(&articles, dist_dir)?;
+    feeds::generate_feeds(site, &articles, &tag_map, dist_dir)?;
     redirects::generate_and_write_redirects(&articles, dist_dir)?;
 
+    let pruned = build_cache.prune()?;
+    if pruned > 0 {
+        println!("Build cache: pruned {pruned} stale entries");
+    }
+    build_cache.report();
+
     Ok(())
 }
@@ -0,0 +1,317 @@
+//! インクリメンタルビルド用キャッシュ
+//!
+//! `.dnfolio-cache/` 以下に記事のパース結果・OGP PNG・画像の縮小版をコンテンツハッシュで保存し、
+//! ソース・テンプレートが変わっていなければ前回の結果を再利用する。
+//! キーにはSSG自体のビルドID（`src/` のハッシュ）と、ハイライトのテーマ・構文定義・
+//! OGPのフォントも含めるので、これらを変えると手動の操作なしでキャッシュが無効になる。
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use anyhow::{Context, Result};
+
+use crate::highlight;
+use crate::models::Article;
+use crate::ogp;
+
+/// キャッシュディレクトリ（リポジトリルートからの相対パス）
+pub const CACHE_DIR: &str = ".dnfolio-cache";
+
+/// SSGのソース（コード・テンプレート）のハッシュ（build_script.rsで計算する）
+const BUILD_ID: &str = env!("DNFOLIO_BUILD_ID");
+
+const ARTICLES_DIR: &str = "articles";
+const OGP_DIR: &str = "ogp";
+const IMAGES_DIR: &str = "images";
+
+#[derive(Default)]
+struct Counter {
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+impl Counter {
+    fn hit(&self) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn miss(&self) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn get(&self) -> (usize, usize) {
+        (
+            self.hits.load(Ordering::Relaxed),
+            self.misses.load(Ordering::Relaxed),
+        )
+    }
+}
+
+/// SSGのビルドID・バージョンと、埋め込みのテーマ・構文定義、OGPのフォントのハッシュ
+fn fingerprint() -> Result<[u8; 32]> {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(BUILD_ID.as_bytes());
+    hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+    for theme in highlight::THEMES {
+        hasher.update(theme.as_bytes());
+    }
+    hasher.update(highlight::SYNTAX_DUMP);
+    for font_path in ogp::FONT_PATHS {
+        let font = fs::read(font_path).with_context(|| format!("failed to read {font_path}"))?;
+        hasher.update(&font);
+    }
+    Ok(*hasher.finalize().as_bytes())
+}
+
+pub struct BuildCache {
+    /// `None` の場合はキャッシュ無効（`--no-cache`）
+    root: Option<PathBuf>,
+    /// 全てのキーに含める、キャッシュした結果を左右する入力のハッシュ
+    fingerprint: [u8; 32],
+    /// 今回のビルドで参照したエントリ（prune対象外）
+    used: Mutex<HashSet<PathBuf>>,
+    /// 今回のビルドで生成・読み込みした画像の縮小版
+    ///
+    /// 寸法を調べるときと書き出すときで同じ縮小版を2回エンコードしない（`--no-cache` でも有効）
+    variants: Mutex<HashMap<String, Vec<u8>>>,
+    articles: Counter,
+    ogp: Counter,
+    images: Counter,
+}
+
+impl BuildCache {
+    pub fn open(root: &Path) -> Result<Self> {
+        fs::create_dir_all(root.join(ARTICLES_DIR))?;
+        fs::create_dir_all(root.join(OGP_DIR))?;
+        fs::create_dir_all(root.join(IMAGES_DIR))?;
+        Ok(Self {
+            root: Some(root.to_path_buf()),
+            fingerprint: fingerprint()?,
+            used: Mutex::new(HashSet::new()),
+            variants: Mutex::new(HashMap::new()),
+            articles: Counter::default(),
+            ogp: Counter::default(),
+            images: Counter::default(),
+        })
+    }
+
+    pub fn disabled() -> Self {
+        Self {
+            root: None,
+            fingerprint: [0; 32],
+            used: Mutex::new(HashSet::new()),
+            variants: Mutex::new(HashMap::new()),
+            articles: Counter::default(),
+            ogp: Counter::default(),
+            images: Counter::default(),
+        }
+    }
+
+    /// フィンガープリントと入力からキーを生成する
+    fn key(&self, parts: &[&[u8]]) -> String {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&self.fingerprint);
+        for part in parts {
+            // 区切りが曖昧にならないよう長さを前置する
+            hasher.update(&(part.len() as u64).to_le_bytes());
+            hasher.update(part);
+        }
+        hasher.finalize().to_hex().to_string()
+    }
+
+    pub fn article_key(&self, source_path: &Path, source: &[u8], dist_dir: &Path) -> String {
+        self.key(&[
+            source_path.to_string_lossy().as_bytes(),
+            source,
+            dist_dir.to_string_lossy().as_bytes(),
+        ])
+    }
+
+    fn entry_path(&self, dir: &str, file_name: &str) -> Option<PathBuf> {
+        let path = self.root.as_ref()?.join(dir).join(file_name);
+        self.used
+            .lock()
+            .expect("cache lock poisoned")
+            .insert(path.clone());
+        Some(path)
+    }
+
+    /// キャッシュ済みの記事を読み込む（ヒット時のみカウント）
+    pub fn load_article(&self, key: &str) -> Option<Article> {
+        let path = self.entry_path(ARTICLES_DIR, &format!("{key}.json"))?;
+        let data = fs::read(&path).ok()?;
+        match serde_json::from_slice(&data) {
+            Ok(article) => {
+                self.articles.hit();
+                Some(article)
+            }
+            Err(e) => {
+                eprintln!("Warning: 壊れたキャッシュを無視します {path:?}: {e}");
+                None
+            }
+        }
+    }
+
+    /// パースした記事をキャッシュに保存する（ミスとしてカウント）
+    pub fn store_article(&self, key: &str, article: &Article) -> Result<()> {
+        self.articles.miss();
+        if let Some(path) = self.entry_path(ARTICLES_DIR, &format!("{key}.json")) {
+            fs::write(path, serde_json::to_vec(article)?)?;
+        }
+        Ok(())
+    }
+
+    /// OGP PNGをキャッシュから取り出す。なければ `render` で生成してキャッシュする
+    ///
+    /// キーはSVGの内容そのもの（タイトル・テンプレート・アイコンを全て含む）。フォントはフィンガープリントに含む
+    pub fn ogp_png(
+        &self,
+        svg_data: &[u8],
+        dest: &Path,
+        render: impl FnOnce(&Path) -> Result<()>,
+    ) -> Result<()> {
+        let file_name = format!("{}.png", self.key(&[svg_data]));
+        self.cached_file(OGP_DIR, &file_name, &self.ogp, dest, render)
+    }
+
+    /// 画像の縮小版をキャッシュから取り出す。なければ `encode` で生成してキャッシュする
+    ///
+    /// キーは元画像のバイト列と縮小後の幅
+    pub fn image_variant(
+        &self,
+        source: &[u8],
+        width: u32,
+        extension: &str,
+        encode: impl FnOnce() -> Result<Vec<u8>>,
+    ) -> Result<Vec<u8>> {
+        let file_name = format!("{}.{extension}", self.key(&[source, &width.to_le_bytes()]));
+        if let Some(data) = self
+            .variants
+            .lock()
+            .expect("cache lock poisoned")
+            .get(&file_name)
+        {
+            return Ok(data.clone());
+        }
+
+        let data = self.load_or_encode_variant(&file_name, encode)?;
+        self.variants
+            .lock()
+            .expect("cache lock poisoned")
+            .insert(file_name, data.clone());
+        Ok(data)
+    }
+
+    fn load_or_encode_variant(
+        &self,
+        file_name: &str,
+        encode: impl FnOnce() -> Result<Vec<u8>>,
+    ) -> Result<Vec<u8>> {
+        let Some(cached) = self.entry_path(IMAGES_DIR, file_name) else {
+            return encode();
+        };
+
+        if let Ok(data) = fs::read(&cached) {
+            self.images.hit();
+            return Ok(data);
+        }
+        let data = encode()?;
+        fs::write(&cached, &data)?;
+        self.images.miss();
+        Ok(data)
+    }
+
+    fn cached_file(
+        &self,
+        dir: &str,
+        file_name: &str,
+        counter: &Counter,
+        dest: &Path,
+        render: impl FnOnce(&Path) -> Result<()>,
+    ) -> Result<()> {
+        let Some(cached) = self.entry_path(dir, file_name) else {
+            return render(dest);
+        };
+
+        if cached.exists() {
+            fs::copy(&cached, dest)?;
+            counter.hit();
+        } else {
+            render(dest)?;
+            fs::copy(dest, &cached)?;
+            counter.miss();
+        }
+        Ok(())
+    }
+
+    /// 今回のビルドで参照されなかったエントリを削除する
+    pub fn prune(&self) -> Result<usize> {
+        let Some(root) = &self.root else {
+            return Ok(0);
+        };
+        let used = self.used.lock().expect("cache lock poisoned");
+        let mut removed = 0;
+        for dir in [ARTICLES_DIR, OGP_DIR, IMAGES_DIR] {
+            for entry in fs::read_dir(root.join(dir))? {
+                let path = entry?.path();
+                if path.is_file() && !used.contains(&path) {
+                    fs::remove_file(&path)?;
+                    removed += 1;
+                }
+            }
+        }
+        Ok(removed)
+    }
+
+    pub fn report(&self) {
+        if self.root.is_none() {
+            println!("Build cache: disabled");
+            return;
+        }
+        let (article_hits, article_misses) = self.articles.get();
+        let (ogp_hits, ogp_misses) = self.ogp.get();
+        let (image_hits, image_misses) = self.images.get();
+        println!(
+            "Build cache: articles {article_hits} hit / {article_misses} miss, OGP {ogp_hits} hit / {ogp_misses} miss, images {image_hits} hit / {image_misses} miss"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_depends_on_fingerprint() {
+        let mut cache = BuildCache::disabled();
+        let key = cache.key(&[b"source"]);
+        assert_eq!(key, cache.key(&[b"source"]));
+        assert_ne!(key, cache.key(&[b"sour", b"ce"]));
+
+        cache.fingerprint = [1; 32];
+        assert_ne!(key, cache.key(&[b"source"]));
+    }
+
+    #[test]
+    fn test_image_variant_is_encoded_once() {
+        let cache = BuildCache::disabled();
+        let encodes = AtomicUsize::new(0);
+        let encode = || {
+            encodes.fetch_add(1, Ordering::Relaxed);
+            Ok(b"variant".to_vec())
+        };
+        assert_eq!(
+            cache.image_variant(b"source", 560, "jpg", encode).unwrap(),
+            b"variant"
+        );
+        assert_eq!(
+            cache.image_variant(b"source", 560, "jpg", encode).unwrap(),
+            b"variant"
+        );
+        cache.image_variant(b"source", 1120, "jpg", encode).unwrap();
+        assert_eq!(encodes.load(Ordering::Relaxed), 2);
+    }
+}
@@ -0,0 +1,324 @@
+//! コンテンツのLint（`dnfolio check`）
+//!
+//! dist/ には何も書き込まずに記事のfront matterやリンクを検査し、
+//! `file:line` 形式で問題を報告する。エラーがあれば非ゼロで終了する。
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use walkdir::WalkDir;
+
+use crate::build;
+use crate::config::SiteConfig;
+use crate::dates;
+use crate::front_matter::{self, key_line};
+use crate::history::{self, Revision};
+use crate::i18n;
+use crate::models::InternalLink;
+use crate::publish;
+
+const CONTENT_DIR: &str = "content";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Error,
+    /// 報告するだけでビルドは失敗させない
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub path: PathBuf,
+    pub line: usize,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}: {}",
+            self.path.display(),
+            self.line,
+            self.severity,
+            self.message
+        )
+    }
+}
+
+/// 本文中の内部リンクのうち記事（`/posts/<slug>/`）へのものを行番号付きで返す
+fn post_links(site: &SiteConfig, links: Vec<InternalLink>) -> Vec<(usize, String)> {
+    links
+        .into_iter()
+        .filter_map(|link| {
+            let path = link
+                .url
+                .strip_prefix(site.base_url.as_str())
+                .unwrap_or(&link.url);
+            let slug = path
+                .split(['#', '?'])
+                .next()?
+                .strip_prefix("/posts/")?
+                .trim_end_matches('/');
+            Some((link.line, slug.to_string()))
+        })
+        .collect()
+}
+
+struct CheckedArticle {
+    path: PathBuf,
+    slug: String,
+    slug_line: usize,
+    draft: bool,
+    post_links: Vec<(usize, String)>,
+}
+
+fn check_file(
+    site: &SiteConfig,
+    now: DateTime<FixedOffset>,
+    path: &Path,
+    last_commit: Option<&Revision>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<Option<CheckedArticle>> {
+    let source = fs::read_to_string(path)?;
+    let mut report = |line: usize, message: String| {
+        diagnostics.push(Diagnostic {
+            path: path.to_path_buf(),
+            line,
+            severity: Severity::Error,
+            message,
+        });
+    };
+
+    let front_matter = match front_matter::parse(&source) {
+        Ok(front_matter) => front_matter,
+        Err(e) => {
+            let (line, message) = front_matter::error_location(&source, &e);
+            report(line, format!("front matterを解析できません: {message}"));
+            return Ok(None);
+        }
+    };
+    let Some(metadata) = front_matter.metadata else {
+        report(
+            1,
+            format!(
+                "front matter ({0} ... {0}) がありません",
+                front_matter::DELIMITER
+            ),
+        );
+        return Ok(None);
+    };
+
+    if metadata.title.trim().is_empty() {
+        report(key_line(&source, "title"), "タイトルが空です".to_string());
+    }
+
+    for (key, value) in [
+        ("created", &metadata.created),
+        ("updated", &metadata.updated),
+    ] {
+        if let Some(value) = value
+            && NaiveDate::parse_from_str(value, "%Y-%m-%d").is_err()
+        {
+            report(
+                key_line(&source, key),
+                format!("`{key}` の日付 \"{value}\" を解析できません（YYYY-MM-DD形式）"),
+            );
+        }
+    }
+    if let Some(publish_at) = &metadata.publish_at
+        && dates::parse_datetime(publish_at).is_none()
+    {
+        report(
+            key_line(&source, "publish_at"),
+            format!(
+                "`publish_at` の日時 \"{publish_at}\" を解析できません（YYYY-MM-DDかRFC 3339形式）"
+            ),
+        );
+    }
+
+    if metadata.created.is_none() && dates::extract_date_from_path(path).is_none() {
+        report(
+            1,
+            "公開日がありません（`created` かファイル名の日付が必要です）".to_string(),
+        );
+    }
+
+    if let Some(languages) = metadata
+        .taxonomies
+        .as_ref()
+        .and_then(|t| t.languages.as_ref())
+    {
+        for language in languages {
+            if !i18n::is_supported(language) {
+                report(
+                    key_line(&source, "languages"),
+                    format!("未知の言語 \"{language}\" です"),
+                );
+            }
+        }
+    }
+
+    let links = match build::collect_links(&front_matter.body, front_matter.body_line) {
+        Ok(links) => links,
+        Err((line, message)) => {
+            report(line, message);
+            Vec::new()
+        }
+    };
+
+    // 本文をコミットしたのに `updated` を更新し忘れていないか
+    if let Some(updated) = metadata.updated.as_deref()
+        && let Ok(updated_date) = NaiveDate::parse_from_str(updated, "%Y-%m-%d")
+        && let Some(revision) = last_commit
+        && updated_date < revision.date.date_naive()
+    {
+        diagnostics.push(Diagnostic {
+            path: path.to_path_buf(),
+            line: key_line(&source, "updated"),
+            severity: Severity::Warning,
+            message: format!(
+                "`updated` ({updated}) が最後のコミット ({} {}) より古いです",
+                revision.short_hash(),
+                revision.date.format("%Y-%m-%d")
+            ),
+        });
+    }
+
+    Ok(Some(CheckedArticle {
+        path: path.to_path_buf(),
+        slug: build::article_slug(path, Some(&metadata)),
+        slug_line: key_line(&source, "slug"),
+        draft: !publish::visibility(Some(&metadata), now).is_published(),
+        post_links: post_links(site, links),
+    }))
+}
+
+/// 記事をまたいだ検査（slugの重複、公開記事から下書きへのリンク）
+fn check_across_articles(articles: &[CheckedArticle], diagnostics: &mut Vec<Diagnostic>) {
+    let mut by_slug: HashMap<&str, Vec<&CheckedArticle>> = HashMap::new();
+    for article in articles {
+        by_slug.entry(&article.slug).or_default().push(article);
+    }
+
+    for article in articles {
+        let same_slug = &by_slug[article.slug.as_str()];
+        if same_slug.len() > 1 {
+            let others: Vec<String> = same_slug
+                .iter()
+                .filter(|other| other.path != article.path)
+                .map(|other| other.path.display().to_string())
+                .collect();
+            diagnostics.push(Diagnostic {
+                path: article.path.clone(),
+                line: article.slug_line,
+                severity: Severity::Error,
+                message: format!(
+                    "slug \"{}\" が重複しています: {}",
+                    article.slug,
+                    others.join(", ")
+                ),
+            });
+        }
+
+        if article.draft {
+            continue;
+        }
+        for (line, slug) in &article.post_links {
+            let Some(targets) = by_slug.get(slug.as_str()) else {
+                continue;
+            };
+            if targets.iter().all(|target| target.draft) {
+                diagnostics.push(Diagnostic {
+                    path: article.path.clone(),
+                    line: *line,
+                    severity: Severity::Error,
+                    message: format!("下書き記事 /posts/{slug}/ へリンクしています"),
+                });
+            }
+        }
+    }
+}
+
+/// `now` は予約投稿の公開判定に使う（`build` と同じく `--now` か `dates::build_time()`）
+pub fn run(site: &SiteConfig, now: DateTime<FixedOffset>) -> Result<()> {
+    let mut markdown_files: Vec<PathBuf> = WalkDir::new(CONTENT_DIR)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.into_path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "md"))
+        .collect();
+    markdown_files.sort();
+
+    let history = history::load(&[Path::new(CONTENT_DIR)]);
+    let mut diagnostics = Vec::new();
+    let mut articles = Vec::new();
+    for path in &markdown_files {
+        let last_commit = history.get(path).and_then(|revisions| revisions.first());
+        if let Some(article) = check_file(site, now, path, last_commit, &mut diagnostics)? {
+            articles.push(article);
+        }
+    }
+    check_across_articles(&articles, &mut diagnostics);
+
+    diagnostics.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+    for diagnostic in &diagnostics {
+        eprintln!("{diagnostic}");
+    }
+
+    let errors = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == Severity::Error)
+        .count();
+    let warnings = diagnostics.len() - errors;
+    println!(
+        "Checked {} files: {errors} errors, {warnings} warnings",
+        markdown_files.len()
+    );
+
+    if errors > 0 {
+        anyhow::bail!("content check failed with {errors} errors");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_post_links_include_shortcodes() {
+        let site: SiteConfig = toml::from_str(
+            "base_url = \"https://example.com\"\ntitle = \"t\"\ndescription = \"d\"\n[author]\nname = \"a\"\n",
+        )
+        .unwrap();
+        let body = concat!(
+            "[a](/posts/a/#top)\n",
+            "\n",
+            "{{ link_card(url=\"https://example.com/posts/b/\") }}\n",
+            "\n",
+            "```\n",
+            "{{ link_card(url=\"/posts/c/\") }}\n",
+            "```\n",
+        );
+        let links = build::collect_links(body, 10).unwrap();
+        assert_eq!(
+            post_links(&site, links),
+            vec![(10, "a".to_string()), (12, "b".to_string())]
+        );
+    }
+}
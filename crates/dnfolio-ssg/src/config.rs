@@ -0,0 +1,104 @@
+//! サイト設定モジュール
+//!
+//! `dnfolio.toml` を読み込み、各ジェネレーターに渡す `SiteConfig` を提供する。
+//! フォークやステージング環境ではTOMLを書き換えるだけでURL等を差し替えられる。
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::Context;
+use serde::Deserialize;
+
+/// デフォルトの設定ファイルパス
+pub const DEFAULT_CONFIG_PATH: &str = "dnfolio.toml";
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct AuthorConfig {
+    /// 構造化データ等で使う表示名
+    pub name: String,
+    /// `<meta name="author">` に使うフルネーム（未指定時は `name`）
+    #[serde(default)]
+    pub full_name: Option<String>,
+    /// Twitter(X)のハンドル（`@` 付き）
+    #[serde(default)]
+    pub twitter: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct SiteConfig {
+    /// サイトのベースURL（末尾スラッシュなし）
+    pub base_url: String,
+    pub title: String,
+    pub description: String,
+    #[serde(default = "default_language")]
+    pub language: String,
+    pub author: AuthorConfig,
+    #[serde(default)]
+    pub feed: FeedConfig,
+    /// ソースのリポジトリURL（更新履歴のコミットへのリンクに使う）
+    #[serde(default)]
+    pub repository: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct FeedConfig {
+    /// フィードに含める記事数
+    #[serde(default = "default_feed_limit")]
+    pub limit: usize,
+    /// 説明文だけでなく本文全体を含める
+    #[serde(default)]
+    pub full_content: bool,
+}
+
+impl Default for FeedConfig {
+    fn default() -> Self {
+        Self {
+            limit: default_feed_limit(),
+            full_content: false,
+        }
+    }
+}
+
+fn default_language() -> String {
+    "ja".to_string()
+}
+
+fn default_feed_limit() -> usize {
+    20
+}
+
+impl SiteConfig {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("設定ファイルを読み込めません: {}", path.display()))?;
+        let mut config: SiteConfig = toml::from_str(&raw)
+            .with_context(|| format!("設定ファイルの形式が不正です: {}", path.display()))?;
+        config.base_url = config.base_url.trim_end_matches('/').to_string();
+        Ok(config)
+    }
+
+    /// サイト内パスを絶対URLに変換する
+    pub fn absolute_url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// トップページの絶対URL
+    pub fn home_url(&self) -> String {
+        self.absolute_url("/")
+    }
+
+    pub fn author_full_name(&self) -> &str {
+        self.author
+            .full_name
+            .as_deref()
+            .unwrap_or(&self.author.name)
+    }
+
+    /// robots.txtの内容を生成する
+    pub fn robots_txt(&self) -> String {
+        format!(
+            "User-agent: *\nAllow: /\n\nSitemap: {}\n",
+            self.absolute_url("/sitemap.xml")
+        )
+    }
+}
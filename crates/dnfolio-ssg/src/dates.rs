@@ -1,6 +1,6 @@
 use std::path::Path;
 
-use chrono::{DateTime, FixedOffset, NaiveDate, TimeZone};
+use chrono::{DateTime, FixedOffset, NaiveDate, TimeZone, Utc};
 
 use crate::models::{Article, MetaData};
 
@@ -23,6 +23,30 @@ fn start_of_day(date: NaiveDate) -> Option<DateTime<FixedOffset>> {
         .and_then(|naive_dt| jst().from_local_datetime(&naive_dt).single())
 }
 
+/// 現在時刻（JST）
+pub fn now() -> DateTime<FixedOffset> {
+    Utc::now().with_timezone(&jst())
+}
+
+/// ビルド日時（`SOURCE_DATE_EPOCH` があればその時刻）
+///
+/// 同じコミットから同じ `dist/` を再現できるよう、出力に埋め込む時刻はここから取る
+pub fn build_time() -> DateTime<FixedOffset> {
+    std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.trim().parse::<i64>().ok())
+        .and_then(|epoch| DateTime::from_timestamp(epoch, 0))
+        .map(|time| time.with_timezone(&jst()))
+        .unwrap_or_else(now)
+}
+
+/// `YYYY-MM-DD`（JSTの0時）またはRFC 3339形式の日時を解析する
+pub fn parse_datetime(value: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .or_else(|| parse_date(Some(value)).and_then(start_of_day))
+}
+
 pub fn extract_date_from_path(path: &Path) -> Option<NaiveDate> {
     let file_name = path.file_stem()?.to_str()?;
     let date_part = file_name.split('_').next()?;
@@ -51,8 +75,17 @@ pub fn resolve_metadata_dates(
     })
 }
 
+/// 記事の公開日・更新日（`updated` がなければ最後にコミットされた日時を更新日とする）
 pub fn resolve_article_dates(article: &Article) -> Option<ResolvedArticleDates> {
-    resolve_metadata_dates(article.metadata.as_ref(), &article.source_path)
+    let mut resolved = resolve_metadata_dates(article.metadata.as_ref(), &article.source_path)?;
+    let has_updated = article
+        .metadata
+        .as_ref()
+        .is_some_and(|meta| parse_date(meta.updated.as_deref()).is_some());
+    if !has_updated && let Some(revision) = article.revisions.first() {
+        resolved.modified = revision.date.with_timezone(&jst()).max(resolved.published);
+    }
+    Some(resolved)
 }
 
 pub fn latest_article_lastmod(articles: &[Article]) -> Option<DateTime<FixedOffset>> {
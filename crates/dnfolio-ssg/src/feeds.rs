@@ -0,0 +1,369 @@
+//! フィード生成モジュール
+//!
+//! サイト全体のRSS 2.0（`/feed.xml`）・Atom（`/atom.xml`）・JSON Feed 1.1（`/feed.json`）と、
+//! タグごと・言語ごとのRSS（`/tags/<slug>/feed.xml`, `/<lang>/feed.xml`）を出力する。
+//! `dnfolio.toml` の `[feed] full_content = true` で本文全体を含める。
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use atom_syndication as atom;
+use chrono::{DateTime, FixedOffset};
+use rss::{CategoryBuilder, ChannelBuilder, GuidBuilder, ItemBuilder};
+
+use crate::config::SiteConfig;
+use crate::dates;
+use crate::i18n;
+use crate::models::{Article, TagInfo};
+use crate::tags;
+
+pub const RSS_PATH: &str = "/feed.xml";
+pub const ATOM_PATH: &str = "/atom.xml";
+pub const JSON_FEED_PATH: &str = "/feed.json";
+/// サイト全体のフィード（リンク検証で生成済みURLとして扱う）
+pub const FEED_PATHS: [&str; 3] = [RSS_PATH, ATOM_PATH, JSON_FEED_PATH];
+
+const CONTENT_NAMESPACE: &str = "http://purl.org/rss/1.0/modules/content/";
+
+/// タグごとのRSSのURL
+pub fn tag_feed_url(tag_name: &str) -> String {
+    format!("{}feed.xml", tags::tag_url(tag_name))
+}
+
+/// フィードの1件分（3形式で共通の情報）
+struct FeedItem<'a> {
+    title: &'a str,
+    url: String,
+    description: Option<&'a str>,
+    /// `full_content` 有効時のみ。サイト内の相対URLは絶対URLに書き換え済み
+    content_html: Option<String>,
+    published: DateTime<FixedOffset>,
+    modified: DateTime<FixedOffset>,
+    tags: &'a [String],
+}
+
+fn feed_items<'a>(site: &SiteConfig, articles: &[&'a Article]) -> Vec<FeedItem<'a>> {
+    articles
+        .iter()
+        .take(site.feed.limit)
+        .filter_map(|article| {
+            let meta = article.metadata.as_ref()?;
+            let article_dates = dates::resolve_article_dates(article)?;
+            let url = site.absolute_url(&article.relative_url.to_string_lossy());
+            let content_html = site
+                .feed
+                .full_content
+                .then(|| absolutize_urls(&article.content_html, &site.base_url, &url));
+            Some(FeedItem {
+                title: &meta.title,
+                url,
+                description: meta.description.as_deref(),
+                content_html,
+                published: article_dates.published,
+                modified: article_dates.modified,
+                tags: meta
+                    .taxonomies
+                    .as_ref()
+                    .and_then(|t| t.tags.as_deref())
+                    .unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// 属性値のURLを絶対URLにする（`/path` はサイトのURL、`#id` は記事のURLを前に付ける）
+fn absolutize_url(value: &str, base_url: &str, article_url: &str) -> String {
+    if value.starts_with('/') && !value.starts_with("//") {
+        format!("{base_url}{value}")
+    } else if value.starts_with('#') {
+        format!("{article_url}{value}")
+    } else {
+        value.to_string()
+    }
+}
+
+/// 本文HTMLの `href`・`src`・`srcset` に含まれる相対URLを絶対URLに書き換える
+///
+/// フィードリーダーは記事のURLを基準に解決してくれるとは限らないため
+fn absolutize_urls(html: &str, base_url: &str, article_url: &str) -> String {
+    const ATTRIBUTES: [&str; 3] = [" href=\"", " src=\"", " srcset=\""];
+
+    let mut output = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some((start, attribute)) = ATTRIBUTES
+        .iter()
+        .filter_map(|attribute| rest.find(attribute).map(|start| (start, *attribute)))
+        .min_by_key(|(start, _)| *start)
+    {
+        let value_start = start + attribute.len();
+        let Some(value_len) = rest[value_start..].find('"') else {
+            break;
+        };
+        let value = &rest[value_start..value_start + value_len];
+        output.push_str(&rest[..value_start]);
+        if attribute == " srcset=\"" {
+            let candidates: Vec<String> = value
+                .split(", ")
+                .map(|candidate| absolutize_url(candidate, base_url, article_url))
+                .collect();
+            output.push_str(&candidates.join(", "));
+        } else {
+            output.push_str(&absolutize_url(value, base_url, article_url));
+        }
+        rest = &rest[value_start + value_len..];
+    }
+    output.push_str(rest);
+    output
+}
+
+fn rss_xml(
+    site: &SiteConfig,
+    language: &str,
+    title: &str,
+    link: &str,
+    description: &str,
+    items: &[FeedItem],
+) -> String {
+    let items: Vec<rss::Item> = items
+        .iter()
+        .map(|item| {
+            // タグからカテゴリを生成
+            let categories: Vec<rss::Category> = item
+                .tags
+                .iter()
+                .map(|tag| CategoryBuilder::default().name(tag.clone()).build())
+                .collect();
+
+            ItemBuilder::default()
+                .title(Some(item.title.to_string()))
+                .link(Some(item.url.clone()))
+                .description(item.description.map(String::from))
+                .content(item.content_html.clone())
+                .pub_date(Some(item.published.to_rfc2822()))
+                .categories(categories)
+                .guid(Some(
+                    GuidBuilder::default()
+                        .value(item.url.clone())
+                        .permalink(true)
+                        .build(),
+                ))
+                .build()
+        })
+        .collect();
+
+    let mut channel = ChannelBuilder::default();
+    channel
+        .title(title.to_string())
+        .link(link.to_string())
+        .description(description.to_string())
+        .language(Some(language.to_string()))
+        .items(items);
+    if site.feed.full_content {
+        channel.namespace(("content".to_string(), CONTENT_NAMESPACE.to_string()));
+    }
+    channel.build().to_string()
+}
+
+fn atom_xml(site: &SiteConfig, items: &[FeedItem]) -> String {
+    let author = atom::Person {
+        name: site.author.name.clone(),
+        uri: Some(site.home_url()),
+        ..Default::default()
+    };
+
+    let entries: Vec<atom::Entry> = items
+        .iter()
+        .map(|item| atom::Entry {
+            title: atom::Text::plain(item.title),
+            id: item.url.clone(),
+            updated: item.modified,
+            published: Some(item.published),
+            authors: vec![author.clone()],
+            links: vec![atom::Link {
+                href: item.url.clone(),
+                rel: "alternate".to_string(),
+                ..Default::default()
+            }],
+            categories: item
+                .tags
+                .iter()
+                .map(|tag| atom::Category {
+                    term: tag.clone(),
+                    ..Default::default()
+                })
+                .collect(),
+            summary: item.description.map(atom::Text::plain),
+            content: item.content_html.as_ref().map(|html| atom::Content {
+                value: Some(html.clone()),
+                content_type: Some("html".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .collect();
+
+    let feed = atom::Feed {
+        title: atom::Text::plain(site.title.clone()),
+        subtitle: Some(atom::Text::plain(site.description.clone())),
+        id: site.home_url(),
+        updated: items
+            .iter()
+            .map(|item| item.modified)
+            .max()
+            .unwrap_or_default(),
+        authors: vec![author],
+        links: vec![
+            atom::Link {
+                href: site.home_url(),
+                rel: "alternate".to_string(),
+                ..Default::default()
+            },
+            atom::Link {
+                href: site.absolute_url(ATOM_PATH),
+                rel: "self".to_string(),
+                ..Default::default()
+            },
+        ],
+        lang: Some(site.language.clone()),
+        entries,
+        ..Default::default()
+    };
+    feed.to_string()
+}
+
+fn json_feed(site: &SiteConfig, items: &[FeedItem]) -> String {
+    let items: Vec<serde_json::Value> = items
+        .iter()
+        .map(|item| {
+            let mut json_item = serde_json::json!({
+                "id": item.url,
+                "url": item.url,
+                "title": item.title,
+                "date_published": item.published.to_rfc3339(),
+                "date_modified": item.modified.to_rfc3339(),
+                "tags": item.tags,
+            });
+            if let Some(description) = item.description {
+                json_item["summary"] = serde_json::json!(description);
+            }
+            // content_html か content_text のどちらかが必須
+            match &item.content_html {
+                Some(html) => json_item["content_html"] = serde_json::json!(html),
+                None => {
+                    json_item["content_text"] =
+                        serde_json::json!(item.description.unwrap_or(item.title))
+                }
+            }
+            json_item
+        })
+        .collect();
+
+    serde_json::json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": site.title,
+        "home_page_url": site.home_url(),
+        "feed_url": site.absolute_url(JSON_FEED_PATH),
+        "description": site.description,
+        "language": site.language,
+        "authors": [{ "name": site.author.name, "url": site.home_url() }],
+        "items": items,
+    })
+    .to_string()
+}
+
+fn write_feed(dist_dir: &Path, url: &str, contents: String) -> Result<()> {
+    let path = dist_dir.join(url.trim_start_matches('/'));
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+/// サイト全体とタグごとのフィードを書き出す（`articles` は新しい順に並んでいること）
+pub fn generate_feeds(
+    site: &SiteConfig,
+    articles: &[Article],
+    tag_map: &BTreeMap<String, TagInfo>,
+    dist_dir: &Path,
+) -> Result<()> {
+    let all_articles: Vec<&Article> = articles.iter().collect();
+    let items = feed_items(site, &all_articles);
+
+    write_feed(
+        dist_dir,
+        RSS_PATH,
+        rss_xml(
+            site,
+            &site.language,
+            &site.title,
+            &site.base_url,
+            &site.description,
+            &items,
+        ),
+    )?;
+    write_feed(dist_dir, ATOM_PATH, atom_xml(site, &items))?;
+    write_feed(dist_dir, JSON_FEED_PATH, json_feed(site, &items))?;
+    println!("Generated: feed.xml, atom.xml, feed.json");
+
+    for (tag_name, tag_info) in tag_map {
+        let tag_articles: Vec<&Article> = tag_info.articles.iter().collect();
+        let tag_items = feed_items(site, &tag_articles);
+        write_feed(
+            dist_dir,
+            &tag_feed_url(tag_name),
+            rss_xml(
+                site,
+                &site.language,
+                &format!("{} - タグ: {}", site.title, tag_name),
+                &site.absolute_url(&tags::tag_url(tag_name)),
+                &format!("「{tag_name}」タグの記事"),
+                &tag_items,
+            ),
+        )?;
+    }
+    println!("Generated: {} tag feeds", tag_map.len());
+
+    for (language, language_articles) in i18n::group_by_language(site, articles) {
+        let language_items = feed_items(site, &language_articles);
+        write_feed(
+            dist_dir,
+            &i18n::feed_url(language),
+            rss_xml(
+                site,
+                language,
+                &format!("{} ({})", site.title, i18n::display_name(language)),
+                &site.absolute_url(&i18n::index_url(language)),
+                &site.description,
+                &language_items,
+            ),
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_absolutize_urls() {
+        let html = concat!(
+            r#"<a href="/posts/foo/">foo</a><a href="https://example.com/">ext</a>"#,
+            r##"<a href="#intro">intro</a><img src="/content/a.png" alt="">"##,
+            r#"<source srcset="/content/a-480.jpg 480w, /content/a.jpg 960w">"#,
+        );
+        let expected = concat!(
+            r#"<a href="https://dnfolio.me/posts/foo/">foo</a><a href="https://example.com/">ext</a>"#,
+            r##"<a href="https://dnfolio.me/posts/bar/#intro">intro</a><img src="https://dnfolio.me/content/a.png" alt="">"##,
+            r#"<source srcset="https://dnfolio.me/content/a-480.jpg 480w, https://dnfolio.me/content/a.jpg 960w">"#,
+        );
+        assert_eq!(
+            absolutize_urls(html, "https://dnfolio.me", "https://dnfolio.me/posts/bar/"),
+            expected
+        );
+    }
+}
@@ -0,0 +1,149 @@
+//! front matter（`+++` で囲んだTOML）の解析
+//!
+//! build・check・new・更新履歴はどれもここを通して記事を解析し、
+//! 同じファイルの解釈が食い違わないようにする。
+
+use gray_matter::engine::TOML;
+use gray_matter::{Matter, ParsedEntity, Pod};
+
+use crate::models::MetaData;
+
+pub const DELIMITER: &str = "+++";
+
+/// 記事のfront matterと本文
+pub struct FrontMatter {
+    /// front matterがない・空の場合は `None`
+    pub metadata: Option<MetaData>,
+    pub body: String,
+    /// 本文が始まる行番号（1始まり）
+    pub body_line: usize,
+}
+
+fn matter() -> Matter<TOML> {
+    let mut matter = Matter::<TOML>::new();
+    matter.delimiter = DELIMITER.to_string();
+    matter.close_delimiter = Some(DELIMITER.to_string());
+    matter
+}
+
+/// 記事を解析する（TOMLとして不正、または `MetaData` に合わなければエラー）
+pub fn parse(source: &str) -> Result<FrontMatter, gray_matter::Error> {
+    let parsed: ParsedEntity<MetaData> = matter().parse(source)?;
+    // gray_matterは前後の空行を落とすので末尾から数える
+    let body_line =
+        source.trim_end().lines().count() - parsed.content.trim_end().lines().count() + 1;
+    Ok(FrontMatter {
+        metadata: parsed.data,
+        body: parsed.content,
+        body_line,
+    })
+}
+
+/// 本文だけを取り出す（front matterが壊れていれば全体を本文とみなす）
+pub fn body(source: &str) -> String {
+    matter()
+        .parse::<Pod>(source)
+        .map(|parsed| parsed.content)
+        .unwrap_or_else(|_| source.to_string())
+}
+
+/// front matter内でキーが定義されている行番号（見つからなければ開始行）
+pub fn key_line(source: &str, key: &str) -> usize {
+    source
+        .lines()
+        .enumerate()
+        .skip(1)
+        .take_while(|(_, line)| line.trim_end() != DELIMITER)
+        .find(|(_, line)| {
+            line.trim_start()
+                .strip_prefix(key)
+                .is_some_and(|rest| rest.trim_start().starts_with('='))
+        })
+        .map(|(index, _)| index + 1)
+        .unwrap_or(1)
+}
+
+/// 解析エラーの行番号（TOMLの構文エラーのみ分かる。それ以外は開始行）と1行の説明
+pub fn error_location(source: &str, error: &gray_matter::Error) -> (usize, String) {
+    let text = match error {
+        gray_matter::Error::DeserializeError(text) => text.as_str(),
+        _ => "",
+    };
+    // `TOML parse error at line 2, column 9` の行はfront matter（前後の空行を除く）内の行番号
+    let line = text
+        .split_once("at line ")
+        .and_then(|(_, rest)| rest.split(',').next()?.trim().parse::<usize>().ok())
+        .map(|line| {
+            let leading_blank_lines = source
+                .lines()
+                .skip(1)
+                .take_while(|line| line.trim().is_empty())
+                .count();
+            1 + leading_blank_lines + line
+        })
+        .unwrap_or(1);
+    // 該当箇所の抜粋（`2 | title = ` や `  |  ^`）を除いた説明だけを残す
+    let is_excerpt = |line: &str| {
+        line.starts_with('|')
+            || line
+                .split_once(" |")
+                .is_some_and(|(number, _)| number.chars().all(|c| c.is_ascii_digit()))
+    };
+    let message = text
+        .lines()
+        .skip_while(|line| line.starts_with("TOML parse error"))
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !is_excerpt(line))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let message = if message.is_empty() {
+        error.to_string()
+    } else {
+        message
+    };
+    (line, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let source = "+++\ntitle = \"a\"\nslug = \"b\"\n+++\n\nbody\n";
+        let front_matter = parse(source).unwrap();
+        assert_eq!(front_matter.metadata.unwrap().slug.as_deref(), Some("b"));
+        assert_eq!(front_matter.body, "body");
+        assert_eq!(front_matter.body_line, 6);
+        assert_eq!(key_line(source, "slug"), 3);
+        assert_eq!(key_line(source, "updated"), 1);
+    }
+
+    #[test]
+    fn test_missing_front_matter() {
+        assert!(parse("# no front matter\n").unwrap().metadata.is_none());
+        assert!(parse("+++\ntitle = \"a\"\n").unwrap().metadata.is_none());
+    }
+
+    #[test]
+    fn test_error_location() {
+        let source = "+++\ntitle = \"a\"\ndraft = \n+++\n\nbody\n";
+        let error = parse(source).err().unwrap();
+        let (line, message) = error_location(source, &error);
+        assert_eq!(line, 3);
+        assert!(!message.contains('|'), "{message}");
+        assert!(!message.is_empty());
+    }
+
+    #[test]
+    fn test_body_ignores_front_matter() {
+        assert_eq!(
+            body("+++\ntitle = \"a\"\n+++\n\nbody\n"),
+            body("+++\ntitle = \"b\"\nseries = \"s\"\n+++\nbody\n")
+        );
+        assert_eq!(
+            body("+++\ntitle = \n+++\nbody\n"),
+            "+++\ntitle = \n+++\nbody\n"
+        );
+    }
+}
@@ -0,0 +1,556 @@
+//! コードブロックのシンタックスハイライト
+//!
+//! syntectのクラス付きHTML（`hl-*`）を出力し、配色は同梱の sakurajima テーマ
+//! （`themes/*.tmTheme`）から生成したCSSで当てる。インラインstyleを持たないので
+//! ページのHTMLが小さくなり、配色もエディタと揃う。
+//! 構文定義はsyntectの既定に `syntaxes/*.sublime-syntax`（TOML・Zig・Nix等）を加えたもの。
+
+use std::io::Cursor;
+use std::sync::OnceLock;
+
+use anyhow::Result;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::html::{ClassStyle, ClassedHTMLGenerator, css_for_theme_with_class_style};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+/// `hl-code`・`hl-keyword` のようにクラス名の衝突を避ける
+const CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "hl-" };
+
+const DARK_THEME: &str = include_str!("../themes/sakurajima.tmTheme");
+const LIGHT_THEME: &str = include_str!("../themes/sakurajima-light.tmTheme");
+
+/// 同梱のテーマ（ダーク版・ライト版）
+pub const THEMES: [&str; 2] = [DARK_THEME, LIGHT_THEME];
+
+/// 既定の構文に `syntaxes/` の定義を加えたダンプ（build_script.rsで生成）
+pub const SYNTAX_DUMP: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/syntaxes.packdump"));
+
+static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+static THEME_SCOPES: OnceLock<Vec<Vec<String>>> = OnceLock::new();
+
+/// 既定の構文に `syntaxes/` の定義を加えたもの（build_script.rsでダンプを生成）
+fn get_syntax_set() -> &'static SyntaxSet {
+    SYNTAX_SET.get_or_init(|| {
+        syntect::dumps::from_uncompressed_data(SYNTAX_DUMP)
+            .expect("構文定義のダンプを読み込めません")
+    })
+}
+
+fn load_theme(source: &str) -> Result<Theme> {
+    Ok(ThemeSet::load_from_reader(&mut Cursor::new(source))?)
+}
+
+/// テーマのセレクタを構成するスコープ（`keyword.operator` → `["keyword", "operator"]`）
+fn get_theme_scopes() -> &'static Vec<Vec<String>> {
+    THEME_SCOPES.get_or_init(|| {
+        let mut scopes: Vec<Vec<String>> = THEMES
+            .into_iter()
+            .map(|source| load_theme(source).expect("同梱のテーマを読み込めません"))
+            .flat_map(|theme| theme.scopes)
+            .flat_map(|item| item.scope.selectors)
+            .flat_map(|selector| {
+                selector
+                    .excludes
+                    .into_iter()
+                    .chain([selector.path])
+                    .flat_map(|stack| stack.as_slice().to_vec())
+            })
+            .map(|scope| {
+                scope
+                    .build_string()
+                    .split('.')
+                    .map(str::to_string)
+                    .collect()
+            })
+            .collect();
+        scopes.sort();
+        scopes.dedup();
+        scopes
+    })
+}
+
+/// セレクタ（`.hl-keyword.hl-operator`）の全要素を持つ場合だけその要素のクラスを残す
+fn kept_classes(classes: &str, theme_scopes: &[Vec<String>]) -> String {
+    let atoms: Vec<&str> = classes
+        .split(' ')
+        .filter_map(|class| class.strip_prefix("hl-"))
+        .collect();
+    let matched: Vec<&Vec<String>> = theme_scopes
+        .iter()
+        .filter(|scope| scope.iter().all(|atom| atoms.contains(&atom.as_str())))
+        .collect();
+    atoms
+        .iter()
+        .filter(|atom| matched.iter().any(|scope| scope.iter().any(|a| a == *atom)))
+        .map(|atom| format!("hl-{atom}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `<span class="...">` を読む（クラスと残りの文字列）
+fn parse_span_start(html: &str) -> Option<(&str, &str)> {
+    html.strip_prefix("<span class=\"")?.split_once("\">")
+}
+
+/// テーマのセレクタの一致に関わらないクラスを取り除く
+///
+/// syntectはスコープの全要素（`hl-meta hl-group hl-rust` 等）をクラスとして出力するため、
+/// そのままではインラインstyleより大きくなる。CSSの一致結果が変わらない範囲で
+/// クラスを減らし、クラスが空になった `<span>` は出力しない。
+/// 同じクラスの `<span>` が隣り合う場合は1つにまとめる
+fn prune_classes(html: &str, theme_scopes: &[Vec<String>]) -> String {
+    let mut output = String::with_capacity(html.len());
+    // 開いている `<span>` ごとの出力したクラス（出力しなかったものは `None`）
+    let mut open_spans: Vec<Option<String>> = Vec::new();
+    let mut rest = html;
+    while let Some(index) = rest.find('<') {
+        output.push_str(&rest[..index]);
+        rest = &rest[index..];
+        if let Some(after) = rest.strip_prefix("</span>") {
+            rest = after;
+            let Some(Some(classes)) = open_spans.pop() else {
+                continue;
+            };
+            if let Some((next_classes, after)) = parse_span_start(rest)
+                && kept_classes(next_classes, theme_scopes) == classes
+            {
+                rest = after;
+                open_spans.push(Some(classes));
+                continue;
+            }
+            output.push_str("</span>");
+        } else if let Some((classes, after)) = parse_span_start(rest) {
+            let kept = kept_classes(classes, theme_scopes);
+            if kept.is_empty() {
+                open_spans.push(None);
+            } else {
+                output.push_str(&format!("<span class=\"{kept}\">"));
+                open_spans.push(Some(kept));
+            }
+            rest = after;
+        } else {
+            output.push('<');
+            rest = &rest[1..];
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// フェンスのinfo文字列（```` ```rust,title=src/main.rs,linenos,hl_lines=3-5 ````）
+#[derive(Debug, Default, PartialEq)]
+pub struct CodeFence {
+    pub lang: String,
+    /// ヘッダーに表示するファイル名
+    pub title: Option<String>,
+    /// 行番号を表示するか
+    pub linenos: bool,
+    /// 強調する行（1始まり、両端を含む）
+    pub hl_lines: Vec<(usize, usize)>,
+    /// `diff-rust` のような差分表示か（`lang` には `rust` が入る）
+    pub diff: bool,
+}
+
+impl CodeFence {
+    /// info文字列を解析する
+    ///
+    /// 先頭が言語（`diff-` で始まれば差分表示）、以降はカンマ区切りのオプション。`hl_lines` は空白区切りで
+    /// 複数の範囲を指定できる（`hl_lines=1 3-5`）。解釈できない指定は無視し、
+    /// 警告として返す
+    pub fn parse(info: &str) -> (Self, Vec<String>) {
+        let mut parts = info.split(',').map(str::trim);
+        let lang = parts.next().unwrap_or_default();
+        let mut fence = match lang.strip_prefix("diff-") {
+            Some(lang) if !lang.is_empty() => CodeFence {
+                lang: lang.to_string(),
+                diff: true,
+                ..Default::default()
+            },
+            _ => CodeFence {
+                lang: lang.to_string(),
+                ..Default::default()
+            },
+        };
+        let mut warnings = Vec::new();
+
+        for part in parts.filter(|part| !part.is_empty()) {
+            match part.split_once('=').map(|(k, v)| (k.trim(), v.trim())) {
+                None if part == "linenos" => fence.linenos = true,
+                Some(("title", title)) if !title.is_empty() => {
+                    fence.title = Some(title.to_string());
+                }
+                Some(("hl_lines", ranges)) => {
+                    for range in ranges.split_whitespace() {
+                        match parse_line_range(range) {
+                            Some(range) => fence.hl_lines.push(range),
+                            None => warnings
+                                .push(format!("hl_lines の範囲 \"{range}\" を解析できません")),
+                        }
+                    }
+                }
+                _ => warnings.push(format!("未知のコードブロックのオプション \"{part}\" です")),
+            }
+        }
+        (fence, warnings)
+    }
+
+    fn is_highlighted(&self, line: usize) -> bool {
+        self.hl_lines
+            .iter()
+            .any(|&(start, end)| (start..=end).contains(&line))
+    }
+}
+
+/// `3` や `3-5` を行範囲に変換する
+fn parse_line_range(range: &str) -> Option<(usize, usize)> {
+    let (start, end) = match range.split_once('-') {
+        Some((start, end)) => (start.parse().ok()?, end.parse().ok()?),
+        None => {
+            let line = range.parse().ok()?;
+            (line, line)
+        }
+    };
+    (start >= 1 && start <= end).then_some((start, end))
+}
+
+/// 差分表示の行の種類
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DiffLine {
+    Context,
+    Added,
+    Removed,
+}
+
+/// 差分の行頭の `+` / `-` / 空白を取り除き、行の種類と分ける
+///
+/// 行頭がそれ以外の行（空行等）は変更のない行としてそのまま残す
+fn split_diff(code: &str) -> (String, Vec<DiffLine>) {
+    let mut stripped = String::with_capacity(code.len());
+    let mut kinds = Vec::new();
+    for line in LinesWithEndings::from(code) {
+        let (kind, rest) = match line.as_bytes().first() {
+            Some(b'+') => (DiffLine::Added, &line[1..]),
+            Some(b'-') => (DiffLine::Removed, &line[1..]),
+            Some(b' ') => (DiffLine::Context, &line[1..]),
+            _ => (DiffLine::Context, line),
+        };
+        stripped.push_str(rest);
+        kinds.push(kind);
+    }
+    (stripped, kinds)
+}
+
+/// 行頭で開き直した直後に閉じられる空の `<span>` を取り除く
+fn remove_empty_spans(mut line: String) -> String {
+    while let Some(end) = line.find("\"></span>") {
+        let Some(start) = line[..end].rfind("<span class=\"") else {
+            break;
+        };
+        line.replace_range(start..end + "\"></span>".len(), "");
+    }
+    line
+}
+
+/// ハイライト済みHTMLを行ごとに分ける
+///
+/// 複数行にまたがる `<span>`（ブロックコメント等）は行末で閉じ、次の行で開き直す
+fn split_lines(html: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    let mut open_spans: Vec<&str> = Vec::new();
+    let mut rest = html;
+    while let Some(index) = rest.find(['<', '\n']) {
+        current.push_str(&rest[..index]);
+        rest = &rest[index..];
+        if let Some(after) = rest.strip_prefix('\n') {
+            current.push_str(&"</span>".repeat(open_spans.len()));
+            lines.push(remove_empty_spans(std::mem::take(&mut current)));
+            current.push_str(&open_spans.concat());
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("</span>") {
+            open_spans.pop();
+            current.push_str("</span>");
+            rest = after;
+        } else {
+            let end = rest.find('>').map_or(rest.len(), |end| end + 1);
+            if rest.starts_with("<span") {
+                open_spans.push(&rest[..end]);
+            }
+            current.push_str(&rest[..end]);
+            rest = &rest[end..];
+        }
+    }
+    current.push_str(rest);
+    // 末尾の改行による空行は出力しない
+    let last = remove_empty_spans(current);
+    if !last.is_empty() {
+        lines.push(last);
+    }
+    lines
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// コードをクラス付きの `<pre>` に変換する
+///
+/// 行番号・強調行・差分の指定がある場合だけ、各行を `.code-line` で囲む。
+/// 差分表示では `+` / `-` を除いた行を対象言語でハイライトし、行ごとに印を付ける
+fn highlighted_pre(fence: &CodeFence, code: &str) -> String {
+    let (code, diff_lines) = if fence.diff {
+        split_diff(code)
+    } else {
+        (code.to_string(), Vec::new())
+    };
+    let code = code.as_str();
+
+    let ss = get_syntax_set();
+    // 言語を検索、見つからなければPlainTextにフォールバック
+    let syntax = ss
+        .find_syntax_by_token(&fence.lang)
+        .unwrap_or_else(|| ss.find_syntax_plain_text());
+
+    let mut generator = ClassedHTMLGenerator::new_with_class_style(syntax, ss, CLASS_STYLE);
+    let body = LinesWithEndings::from(code)
+        .try_for_each(|line| generator.parse_html_for_line_which_includes_newline(line))
+        .map(|()| prune_classes(&generator.finalize(), get_theme_scopes()))
+        // エラー時はエスケープしてそのまま表示
+        .unwrap_or_else(|_| escape_html(code));
+
+    if !fence.linenos && fence.hl_lines.is_empty() && !fence.diff {
+        return format!(r#"<pre class="hl-code"><code>{body}</code></pre>"#);
+    }
+
+    let lines: String = split_lines(&body)
+        .into_iter()
+        .enumerate()
+        .map(|(index, line)| {
+            let number = index + 1;
+            let mut class = String::from("code-line");
+            if fence.is_highlighted(number) {
+                class.push_str(" code-line-highlighted");
+            }
+            let mut gutter = String::new();
+            if fence.linenos {
+                gutter.push_str(&format!(
+                    r#"<span class="code-line-number">{number}</span>"#
+                ));
+            }
+            if fence.diff {
+                let (kind_class, marker) = match diff_lines.get(index) {
+                    Some(DiffLine::Added) => (" code-line-added", "+"),
+                    Some(DiffLine::Removed) => (" code-line-removed", "-"),
+                    _ => ("", " "),
+                };
+                class.push_str(kind_class);
+                gutter.push_str(&format!(
+                    r#"<span class="code-diff-marker">{marker}</span>"#
+                ));
+            }
+            format!("<span class=\"{class}\">{gutter}{line}</span>\n")
+        })
+        .collect();
+    let pre_class = if fence.linenos {
+        "hl-code has-line-numbers"
+    } else {
+        "hl-code"
+    };
+    format!(r#"<pre class="{pre_class}"><code>{lines}</code></pre>"#)
+}
+
+/// ヘッダーバー（ファイル名・言語名・コピーボタン）付きのコードブロックを生成する
+pub fn highlight_code(fence: &CodeFence, code: &str) -> String {
+    // 言語表示名（空の場合は"text"）
+    let display_lang = if fence.lang.is_empty() {
+        "text".to_string()
+    } else if fence.diff {
+        format!("diff-{}", fence.lang)
+    } else {
+        fence.lang.clone()
+    };
+    let title_html = fence
+        .title
+        .as_ref()
+        .map(|title| format!(r#"<span class="code-title">{}</span>"#, escape_html(title)))
+        .unwrap_or_default();
+
+    // 差分表示では変更後のコードをコピーさせる
+    let copy_code = if fence.diff {
+        let (stripped, kinds) = split_diff(code);
+        LinesWithEndings::from(&stripped)
+            .zip(kinds)
+            .filter(|(_, kind)| *kind != DiffLine::Removed)
+            .map(|(line, _)| line)
+            .collect()
+    } else {
+        code.to_string()
+    };
+
+    // コードをdata属性用にエスケープ
+    let escaped_code = escape_html(&copy_code).replace('"', "&quot;");
+
+    format!(
+        r#"<div class="code-block-wrapper">
+<div class="code-block-header">
+{}<span class="code-lang">{}</span>
+<button class="code-copy-btn" data-code="{}" title="コピー">
+<svg width="16" height="16" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
+<rect x="9" y="9" width="13" height="13" rx="2" ry="2"></rect>
+<path d="M5 15H4a2 2 0 0 1-2-2V4a2 2 0 0 1 2-2h9a2 2 0 0 1 2 2v1"></path>
+</svg>
+<span class="copy-text">Copy</span>
+</button>
+</div>
+{}</div>"#,
+        title_html,
+        escape_html(&display_lang),
+        escaped_code,
+        highlighted_pre(fence, code)
+    )
+}
+
+/// ハイライト用のCSS（ダーク版を既定、ライト版を `prefers-color-scheme: light` で上書き）
+pub fn stylesheet() -> Result<String> {
+    let dark = css_for_theme_with_class_style(&load_theme(DARK_THEME)?, CLASS_STYLE)?;
+    let light = css_for_theme_with_class_style(&load_theme(LIGHT_THEME)?, CLASS_STYLE)?;
+    Ok(format!(
+        "/* シンタックスハイライト（sakurajima） */\n{dark}\n@media (prefers-color-scheme: light) {{\n{light}}}\n"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highlighted_pre_uses_classes() {
+        let (fence, _) = CodeFence::parse("rust");
+        let html = highlighted_pre(&fence, "fn main() {}\n");
+        assert!(html.starts_with(r#"<pre class="hl-code"><code>"#));
+        assert!(html.contains(r#"class="hl-"#));
+        assert!(!html.contains("style="));
+    }
+
+    #[test]
+    fn test_bundled_syntaxes() {
+        let ss = get_syntax_set();
+        for token in [
+            "toml",
+            "zig",
+            "nix",
+            "kdl",
+            "dockerfile",
+            "vim",
+            "hcl",
+            "tf",
+        ] {
+            assert!(ss.find_syntax_by_token(token).is_some(), "{token}");
+        }
+    }
+
+    #[test]
+    fn test_parse_code_fence() {
+        let (fence, warnings) = CodeFence::parse("rust,title=src/main.rs,linenos,hl_lines=1 3-5");
+        assert_eq!(
+            fence,
+            CodeFence {
+                lang: "rust".to_string(),
+                title: Some("src/main.rs".to_string()),
+                linenos: true,
+                hl_lines: vec![(1, 1), (3, 5)],
+                diff: false,
+            }
+        );
+        assert!(warnings.is_empty());
+
+        let (fence, warnings) = CodeFence::parse("sh,hl_lines=5-3,foo");
+        assert_eq!(fence.lang, "sh");
+        assert!(fence.hl_lines.is_empty());
+        assert_eq!(warnings.len(), 2);
+    }
+
+    #[test]
+    fn test_diff_fence() {
+        let (fence, _) = CodeFence::parse("diff-rust,linenos");
+        assert_eq!(fence.lang, "rust");
+        assert!(fence.diff && fence.linenos);
+        let (fence, _) = CodeFence::parse("diff");
+        assert_eq!(fence.lang, "diff");
+        assert!(!fence.diff);
+
+        let code = " fn main() {\n-    old();\n+    new();\n }\n";
+        let (stripped, kinds) = split_diff(code);
+        assert_eq!(stripped, "fn main() {\n    old();\n    new();\n}\n");
+        assert_eq!(
+            kinds,
+            vec![
+                DiffLine::Context,
+                DiffLine::Removed,
+                DiffLine::Added,
+                DiffLine::Context
+            ]
+        );
+
+        let (fence, _) = CodeFence::parse("diff-rust");
+        let html = highlight_code(&fence, code);
+        assert!(html.contains(
+            r#"data-code="fn main() {
+    new();
+}
+""#
+        ));
+        assert!(html.contains("code-line code-line-removed"));
+        assert!(html.contains("code-line code-line-added"));
+    }
+
+    #[test]
+    fn test_split_lines_reopens_spans() {
+        assert_eq!(
+            split_lines(
+                "<span class=\"hl-comment\">/* a\nb */</span>\nx<span class=\"hl-comment\">// c\n</span>"
+            ),
+            vec![
+                "<span class=\"hl-comment\">/* a</span>",
+                "<span class=\"hl-comment\">b */</span>",
+                "x<span class=\"hl-comment\">// c</span>",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prune_classes() {
+        let scopes = vec![
+            vec!["keyword".to_string(), "operator".to_string()],
+            vec!["meta".to_string(), "attribute".to_string()],
+        ];
+        assert_eq!(
+            prune_classes(
+                r#"<span class="hl-meta hl-group hl-rust"><span class="hl-keyword hl-operator hl-rust">&amp;</span>x</span>"#,
+                &scopes
+            ),
+            r#"<span class="hl-keyword hl-operator">&amp;</span>x"#
+        );
+        assert_eq!(
+            prune_classes(
+                r#"<span class="hl-keyword hl-operator hl-rust">(</span><span class="hl-keyword hl-operator hl-c">)</span>"#,
+                &scopes
+            ),
+            r#"<span class="hl-keyword hl-operator">()</span>"#
+        );
+    }
+
+    #[test]
+    fn test_stylesheet_contains_both_variants() {
+        let css = stylesheet().unwrap();
+        let (dark, light) = css
+            .split_once("@media (prefers-color-scheme: light)")
+            .unwrap();
+        assert!(dark.contains(".hl-code"));
+        assert!(dark.contains("#1a1f26"));
+        assert!(light.contains("#f4f1e8"));
+    }
+}
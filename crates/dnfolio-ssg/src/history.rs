@@ -0,0 +1,262 @@
+//! Gitの履歴
+//!
+//! `git log` から記事・固定ページのファイルごとのコミットを集め、
+//! `updated` がない記事の更新日と、記事末尾の更新履歴に使う。
+//! 本文を変えずにfront matterだけを変更したコミットは数えない。
+//! Gitが使えない環境（ソースの展開のみなど）では履歴なしとして扱う。
+
+use std::collections::{BTreeSet, HashMap};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, FixedOffset};
+use maud::{Markup, html};
+
+use crate::config::SiteConfig;
+use crate::front_matter;
+use crate::i18n::UiStrings;
+
+/// ファイルを変更したコミット
+#[derive(Debug, Clone)]
+pub struct Revision {
+    pub hash: String,
+    pub date: DateTime<FixedOffset>,
+    pub subject: String,
+}
+
+impl Revision {
+    pub fn short_hash(&self) -> &str {
+        &self.hash[..self.hash.len().min(7)]
+    }
+}
+
+/// ファイルパスごとのコミット（新しい順）
+pub type History = HashMap<PathBuf, Vec<Revision>>;
+
+const RECORD_SEPARATOR: char = '\u{1e}';
+/// 追加されたファイルの変更前のblob
+const NULL_BLOB: &str = "0000000000000000000000000000000000000000";
+
+/// コミットでのファイル1つ分の変更（`git log --raw` の1行）
+#[derive(Debug)]
+struct FileChange {
+    path: PathBuf,
+    old_blob: String,
+    new_blob: String,
+}
+
+/// `git log --raw` の出力を解析する（新しい順）
+fn parse_log(output: &str) -> Vec<(Revision, Vec<FileChange>)> {
+    let mut commits = Vec::new();
+    for record in output
+        .split(RECORD_SEPARATOR)
+        .filter(|r| !r.trim().is_empty())
+    {
+        let mut lines = record.lines();
+        let Some(header) = lines.next() else {
+            continue;
+        };
+        let mut fields = header.splitn(3, '\t');
+        let (Some(hash), Some(date), Some(subject)) = (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let Ok(date) = DateTime::parse_from_rfc3339(date) else {
+            continue;
+        };
+        let revision = Revision {
+            hash: hash.to_string(),
+            date,
+            subject: subject.to_string(),
+        };
+        // `:100644 100644 <old> <new> M\t<path>`（リネームは `R086\t<old path>\t<new path>`）
+        let changes = lines
+            .filter_map(|line| {
+                let (meta, paths) = line.strip_prefix(':')?.split_once('\t')?;
+                let mut meta = meta.split_whitespace().skip(2);
+                let (old_blob, new_blob) = (meta.next()?, meta.next()?);
+                Some(FileChange {
+                    path: PathBuf::from(paths.rsplit('\t').next()?),
+                    old_blob: old_blob.to_string(),
+                    new_blob: new_blob.to_string(),
+                })
+            })
+            .collect();
+        commits.push((revision, changes));
+    }
+    commits
+}
+
+/// 本文を変更したコミットだけをファイルごとに集める
+///
+/// front matterだけの変更（`series` の追加や一括整形など）は更新日・更新履歴に含めない
+fn content_history(
+    commits: Vec<(Revision, Vec<FileChange>)>,
+    blobs: &HashMap<String, String>,
+) -> History {
+    let body = |blob: &str| blobs.get(blob).map(|source| front_matter::body(source));
+    let mut history = History::new();
+    for (revision, changes) in commits {
+        for change in changes {
+            let changed_body =
+                change.old_blob == NULL_BLOB || body(&change.old_blob) != body(&change.new_blob);
+            if change.new_blob != NULL_BLOB && changed_body {
+                history
+                    .entry(change.path)
+                    .or_default()
+                    .push(revision.clone());
+            }
+        }
+    }
+    history
+}
+
+/// `git cat-file --batch` でblobの内容をまとめて読む
+fn read_blobs(ids: BTreeSet<&str>) -> Result<HashMap<String, String>> {
+    let mut child = Command::new("git")
+        .args(["cat-file", "--batch"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    let mut stdin = child
+        .stdin
+        .take()
+        .context("failed to open stdin of git cat-file")?;
+    let input: String = ids.iter().map(|id| format!("{id}\n")).collect();
+    // 出力を読みながら書き込まないとパイプが詰まる
+    let writer = thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let output = child.wait_with_output()?;
+    writer.join().expect("git cat-file writer panicked")?;
+    if !output.status.success() {
+        anyhow::bail!("git cat-file failed");
+    }
+
+    // `<id> blob <size>\n<内容>\n` の繰り返し（見つからなければ `<id> missing\n`）
+    let mut blobs = HashMap::new();
+    let mut rest = output.stdout.as_slice();
+    while let Some(newline) = rest.iter().position(|&b| b == b'\n') {
+        let header = String::from_utf8_lossy(&rest[..newline]).into_owned();
+        rest = &rest[newline + 1..];
+        let mut fields = header.split_whitespace();
+        let (Some(id), Some("blob"), Some(size)) = (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let size: usize = size.parse()?;
+        let contents = rest.get(..size).context("truncated git cat-file output")?;
+        blobs.insert(
+            id.to_string(),
+            String::from_utf8_lossy(contents).into_owned(),
+        );
+        rest = rest.get(size + 1..).unwrap_or_default();
+    }
+    Ok(blobs)
+}
+
+fn load_history(paths: &[&Path]) -> Result<History> {
+    let output = Command::new("git")
+        .args(["-c", "core.quotePath=false", "log", "--no-merges"])
+        .arg(format!("--format={RECORD_SEPARATOR}%H%x09%aI%x09%s"))
+        .args(["--raw", "--no-abbrev", "--relative", "--"])
+        .args(paths)
+        .output()
+        .context("git is not available")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git log failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let commits = parse_log(&String::from_utf8_lossy(&output.stdout));
+    let blob_ids = commits
+        .iter()
+        .flat_map(|(_, changes)| changes)
+        .flat_map(|change| [change.old_blob.as_str(), change.new_blob.as_str()])
+        .filter(|&blob| blob != NULL_BLOB)
+        .collect();
+    let blobs = read_blobs(blob_ids)?;
+    Ok(content_history(commits, &blobs))
+}
+
+/// `paths` 以下のファイルの履歴を読み込む（パスはカレントディレクトリからの相対パス）
+pub fn load(paths: &[&Path]) -> History {
+    load_history(paths).unwrap_or_else(|e| {
+        eprintln!("Warning: revision history is disabled: {e}");
+        History::new()
+    })
+}
+
+/// 記事末尾の更新履歴（`details` 要素なので行番号には数えない）
+pub fn markup(site: &SiteConfig, revisions: &[Revision], strings: &UiStrings) -> Markup {
+    html! {
+        @if !revisions.is_empty() {
+            details class="revision-history" {
+                summary { (strings.revision_history) " (" (revisions.len()) ")" }
+                ol {
+                    @for revision in revisions {
+                        li {
+                            time datetime=(revision.date.to_rfc3339()) {
+                                (revision.date.format("%Y-%m-%d"))
+                            }
+                            " "
+                            @if let Some(repository) = &site.repository {
+                                a href=(format!("{repository}/commit/{}", revision.hash)) {
+                                    code { (revision.short_hash()) }
+                                }
+                            } @else {
+                                code { (revision.short_hash()) }
+                            }
+                            " "
+                            span class="revision-subject" { (revision.subject) }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_history_skips_front_matter_only_commits() {
+        let output = concat!(
+            "\u{1e}cccccccccc\t2026-03-01T10:00:00+09:00\tAdd series\n",
+            "\n",
+            ":100644 100644 b2 b3 M\tcontent/2026-01-01_a.md\n",
+            "\u{1e}bbbbbbbbbb\t2026-02-01T10:00:00+09:00\tFix typo\tin title\n",
+            "\n",
+            ":100644 100644 b1 b2 M\tcontent/2026-01-01_a.md\n",
+            "\u{1e}aaaaaaaaaa\t2026-01-01T09:00:00+09:00\tAdd posts\n",
+            "\n",
+            ":000000 100644 0000000000000000000000000000000000000000 b1 A\tcontent/2026-01-01_a.md\n",
+            ":000000 100644 0000000000000000000000000000000000000000 c1 A\tcontent/2026-01-02_日本語.md\n",
+        );
+        let blobs: HashMap<String, String> = [
+            ("b1", "+++\ntitle = \"a\"\n+++\n\nbody\n"),
+            ("b2", "+++\ntitle = \"a\"\n+++\n\nbody, fixed\n"),
+            (
+                "b3",
+                "+++\ntitle = \"a\"\nseries = \"s\"\n+++\n\nbody, fixed\n",
+            ),
+            ("c1", "+++\ntitle = \"c\"\n+++\n"),
+        ]
+        .into_iter()
+        .map(|(id, source)| (id.to_string(), source.to_string()))
+        .collect();
+
+        let history = content_history(parse_log(output), &blobs);
+        let a = &history[Path::new("content/2026-01-01_a.md")];
+        assert_eq!(a.len(), 2);
+        assert_eq!(a[0].short_hash(), "bbbbbbb");
+        assert_eq!(a[0].subject, "Fix typo\tin title");
+        assert_eq!(a[1].subject, "Add posts");
+        assert_eq!(history[Path::new("content/2026-01-02_日本語.md")].len(), 1);
+    }
+}
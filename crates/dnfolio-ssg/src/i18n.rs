@@ -0,0 +1,376 @@
+//! 多言語対応
+//!
+//! 記事の言語はfront matterの `taxonomies.languages` の先頭（なければサイトの言語）。
+//! `translation_key` が同じ記事同士を翻訳として `hreflang` で結び、
+//! 言語ごとに記事一覧（`/<lang>/`）・フィード（`/<lang>/feed.xml`）・404ページを生成する。
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use maud::{Markup, html};
+
+use crate::config::SiteConfig;
+use crate::dates;
+use crate::models::Article;
+use crate::ogp::{OgpCard, OgpRenderer};
+use crate::templates::base::{self, PageConfig};
+
+/// 対応している言語コードと表示名
+pub const LANGUAGES: [(&str, &str); 2] = [("en", "English"), ("ja", "日本語")];
+
+pub fn is_supported(language: &str) -> bool {
+    LANGUAGES.iter().any(|(code, _)| *code == language)
+}
+
+pub fn display_name(language: &str) -> &str {
+    LANGUAGES
+        .iter()
+        .find(|(code, _)| *code == language)
+        .map(|(_, name)| *name)
+        .unwrap_or(language)
+}
+
+/// 記事の言語コード
+pub fn article_language<'a>(site: &'a SiteConfig, article: &'a Article) -> &'a str {
+    article
+        .metadata
+        .as_ref()
+        .and_then(|meta| meta.taxonomies.as_ref())
+        .and_then(|taxonomies| taxonomies.languages.as_ref())
+        .and_then(|languages| languages.first())
+        .map(String::as_str)
+        .filter(|language| is_supported(language))
+        .unwrap_or(&site.language)
+}
+
+/// 言語別の記事一覧ページのURL
+pub fn index_url(language: &str) -> String {
+    format!("/{language}/")
+}
+
+/// 言語別のRSSのURL
+pub fn feed_url(language: &str) -> String {
+    format!("/{language}/feed.xml")
+}
+
+/// 言語ごとのUI文言
+pub struct UiStrings {
+    pub menu: &'static str,
+    pub close_sidebar: &'static str,
+    pub previous_match: &'static str,
+    pub next_match: &'static str,
+    pub search_articles: &'static str,
+    pub filter_by_tag: &'static str,
+    pub search_in_page: &'static str,
+    pub key_bindings: &'static str,
+    pub version_info: &'static str,
+    pub clear_highlight: &'static str,
+    pub easter_egg: &'static str,
+    pub key_move: &'static str,
+    pub key_select: &'static str,
+    pub key_close: &'static str,
+    pub key_toggle_mode: &'static str,
+    pub default_keywords: &'static str,
+    pub back_to_home: &'static str,
+    pub site_info: &'static str,
+    pub not_found_title: &'static str,
+    pub not_found_message: &'static str,
+    /// 言語別の記事一覧の見出し
+    pub articles_in_language: &'static str,
+    /// 読了時間バッジ（`{prefix}{分}{suffix}`）
+    pub reading_time_prefix: &'static str,
+    pub reading_time_suffix: &'static str,
+    /// 読了時間バッジのツールチップ（`{文字数}{characters} / {語数}{words}`）
+    pub reading_characters_suffix: &'static str,
+    pub reading_words_suffix: &'static str,
+    /// 記事冒頭のシリーズボックス（`{series}: <シリーズ名>`）
+    pub series: &'static str,
+    /// 記事末尾のシリーズ内の前後リンク
+    pub series_navigation: &'static str,
+    pub series_previous: &'static str,
+    pub series_next: &'static str,
+    /// 記事末尾の前後の記事・関連記事
+    pub article_navigation: &'static str,
+    pub older_article: &'static str,
+    pub newer_article: &'static str,
+    pub related_articles: &'static str,
+    /// 記事末尾の更新履歴（`{revision_history} (<件数>)`）
+    pub revision_history: &'static str,
+}
+
+const JA: UiStrings = UiStrings {
+    menu: "メニュー",
+    close_sidebar: "サイドバーを閉じる",
+    previous_match: "前へ (N)",
+    next_match: "次へ (n)",
+    search_articles: "記事をGrep検索",
+    filter_by_tag: "タグで絞り込み",
+    search_in_page: "ページ内検索",
+    key_bindings: "キーバインド一覧",
+    version_info: "バージョン情報",
+    clear_highlight: "検索ハイライトを消す",
+    easter_egg: "イースターエッグ",
+    key_move: "移動",
+    key_select: "選択",
+    key_close: "NORMAL→閉じる",
+    key_toggle_mode: "モード切替/閉じる",
+    default_keywords: "ポエム",
+    back_to_home: "ホームに戻る",
+    site_info: "サイト情報",
+    not_found_title: "ページが見つかりません",
+    not_found_message: "お探しのページは見つかりませんでした。",
+    articles_in_language: "日本語の記事",
+    reading_time_prefix: "約",
+    reading_time_suffix: "分で読めます",
+    reading_characters_suffix: "文字",
+    reading_words_suffix: "語",
+    series: "シリーズ",
+    series_navigation: "シリーズ内の前後の記事",
+    series_previous: "← 前の記事",
+    series_next: "次の記事 →",
+    article_navigation: "前後の記事",
+    older_article: "← 古い記事",
+    newer_article: "新しい記事 →",
+    related_articles: "関連記事",
+    revision_history: "更新履歴",
+};
+
+const EN: UiStrings = UiStrings {
+    menu: "Menu",
+    close_sidebar: "Close sidebar",
+    previous_match: "Previous (N)",
+    next_match: "Next (n)",
+    search_articles: "Grep articles",
+    filter_by_tag: "Filter by tag",
+    search_in_page: "Search in page",
+    key_bindings: "Key bindings",
+    version_info: "Version info",
+    clear_highlight: "Clear search highlight",
+    easter_egg: "Easter egg",
+    key_move: "Move",
+    key_select: "Select",
+    key_close: "NORMAL→Close",
+    key_toggle_mode: "Toggle mode/Close",
+    default_keywords: "blog",
+    back_to_home: "Back to home",
+    site_info: "Site info",
+    not_found_title: "Page not found",
+    not_found_message: "The page you were looking for could not be found.",
+    articles_in_language: "Articles in English",
+    reading_time_prefix: "",
+    reading_time_suffix: " min read",
+    reading_characters_suffix: " characters",
+    reading_words_suffix: " words",
+    series: "Series",
+    series_navigation: "Previous and next in this series",
+    series_previous: "← Previous",
+    series_next: "Next →",
+    article_navigation: "Previous and next articles",
+    older_article: "← Older",
+    newer_article: "Newer →",
+    related_articles: "Related articles",
+    revision_history: "Revision history",
+};
+
+pub fn ui(language: &str) -> &'static UiStrings {
+    match language {
+        "en" => &EN,
+        _ => &JA,
+    }
+}
+
+/// 記事を言語ごとにまとめる（記事の並び順は保つ）
+pub fn group_by_language<'a>(
+    site: &'a SiteConfig,
+    articles: &'a [Article],
+) -> BTreeMap<&'a str, Vec<&'a Article>> {
+    let mut groups: BTreeMap<&str, Vec<&Article>> = BTreeMap::new();
+    for article in articles {
+        groups
+            .entry(article_language(site, article))
+            .or_default()
+            .push(article);
+    }
+    groups
+}
+
+/// `translation_key` ごとの翻訳（言語コード, 絶対URL）
+///
+/// 2言語以上そろっているものだけを返す
+pub fn collect_translations(
+    site: &SiteConfig,
+    articles: &[Article],
+) -> HashMap<String, Vec<(String, String)>> {
+    let mut translations: HashMap<String, Vec<(String, String)>> = HashMap::new();
+    for article in articles {
+        if let Some(key) = article
+            .metadata
+            .as_ref()
+            .and_then(|meta| meta.translation_key.as_ref())
+        {
+            translations.entry(key.clone()).or_default().push((
+                article_language(site, article).to_string(),
+                site.absolute_url(&article.relative_url.to_string_lossy()),
+            ));
+        }
+    }
+    translations.retain(|_, versions| {
+        versions.sort();
+        versions.len() > 1
+    });
+    translations
+}
+
+/// 記事の翻訳一覧（`hreflang` 用）
+pub fn article_translations<'a>(
+    translations: &'a HashMap<String, Vec<(String, String)>>,
+    article: &Article,
+) -> Option<&'a [(String, String)]> {
+    article
+        .metadata
+        .as_ref()
+        .and_then(|meta| meta.translation_key.as_ref())
+        .and_then(|key| translations.get(key))
+        .map(Vec::as_slice)
+}
+
+fn sidebar_right_markup(strings: &UiStrings) -> Markup {
+    html! {
+        h2 { (strings.site_info) }
+        ul {
+            li {
+                a href="/" { (strings.back_to_home) }
+            }
+        }
+    }
+}
+
+/// 言語別の記事一覧ページ（`/<lang>/index.html`）を生成する
+pub fn generate_language_indexes(
+    site: &SiteConfig,
+    articles: &[Article],
+    dist_dir: &Path,
+    articles_list_markup: &Markup,
+    ogp_renderer: &OgpRenderer,
+) -> Result<()> {
+    for (language, language_articles) in group_by_language(site, articles) {
+        let strings = ui(language);
+        let url = index_url(language);
+        let page_dir = dist_dir.join(language);
+        fs::create_dir_all(&page_dir)?;
+        let ogp_image_path = ogp_renderer.render_ogp_png(
+            &url,
+            &OgpCard::Language {
+                title: strings.articles_in_language,
+                count: language_articles.len(),
+            },
+        )?;
+
+        let main_content_markup = html! {
+            h1 { (strings.articles_in_language) "(" (language_articles.len()) ")" }
+            ul class="archive-list" {
+                @for article in &language_articles {
+                    @if let Some(meta) = &article.metadata {
+                        li {
+                            @if let Some(article_dates) = dates::resolve_article_dates(article) {
+                                @let date = article_dates.published.format("%Y-%m-%d").to_string();
+                                time datetime=(date) { (date) }
+                                " "
+                            }
+                            a href=(article.relative_url.to_string_lossy().to_string()) { (meta.title) }
+                            @if let Some(ref description) = meta.description {
+                                p class="archive-description" { (description) }
+                            }
+                        }
+                    }
+                }
+            }
+        };
+
+        let feed_title = format!("{} ({})", site.title, display_name(language));
+        let feed_url = feed_url(language);
+        let html_output = base::layout(
+            PageConfig {
+                site,
+                page_title: &format!("{} - {}", strings.articles_in_language, site.title),
+                canonical_url: &site.absolute_url(&url),
+                metadata: None,
+                ogp_image_path: Some(&ogp_image_path),
+                structured_data_html: None,
+                robots_directive: None,
+                article_dates: None,
+                extra_feed: Some((&feed_title, &feed_url)),
+                language: Some(language),
+                translations: None,
+            },
+            articles_list_markup.clone(),
+            main_content_markup,
+            sidebar_right_markup(strings),
+        )
+        .into_string();
+        fs::write(page_dir.join("index.html"), html_output)?;
+    }
+    Ok(())
+}
+
+/// 404ページを生成する（サイトの言語は `/404.html`、それ以外は `/<lang>/404.html`）
+pub fn generate_not_found_pages(
+    site: &SiteConfig,
+    dist_dir: &Path,
+    articles_list_markup: &Markup,
+    ogp_renderer: &OgpRenderer,
+) -> Result<()> {
+    for (language, _) in LANGUAGES {
+        let strings = ui(language);
+        let (page_url, home_url) = if language == site.language {
+            ("/404.html".to_string(), "/".to_string())
+        } else {
+            (format!("/{language}/404.html"), index_url(language))
+        };
+        let ogp_image_path = ogp_renderer.render_ogp_png(
+            &page_url,
+            &OgpCard::NotFound {
+                message: strings.not_found_title,
+            },
+        )?;
+        let main_content = html! {
+            div class="not-found" {
+                h1 class="not-found-code" { "404" }
+                p class="not-found-message" { (strings.not_found_message) }
+                a class="not-found-home" href=(home_url) { (strings.back_to_home) }
+            }
+        };
+
+        let not_found_html = base::layout(
+            PageConfig {
+                site,
+                page_title: &format!("{} - {}", strings.not_found_title, site.title),
+                canonical_url: &site.absolute_url(&page_url),
+                metadata: None,
+                ogp_image_path: Some(&ogp_image_path),
+                structured_data_html: None,
+                robots_directive: Some("noindex,follow"),
+                article_dates: None,
+                extra_feed: None,
+                language: Some(language),
+                translations: None,
+            },
+            articles_list_markup.clone(),
+            main_content,
+            sidebar_right_markup(strings),
+        )
+        .into_string();
+
+        let output_dir = if language == site.language {
+            dist_dir.to_path_buf()
+        } else {
+            dist_dir.join(language)
+        };
+        fs::create_dir_all(&output_dir)?;
+        fs::write(output_dir.join("404.html"), not_found_html)?;
+    }
+    println!("Generated 404.html");
+    Ok(())
+}
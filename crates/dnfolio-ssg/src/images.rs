@@ -0,0 +1,227 @@
+//! レスポンシブ画像
+//!
+//! 本文から参照される `static/` 以下の画像の寸法を読み取り、縮小版を生成して
+//! `<picture>`（srcset・width/height・`loading="lazy"`）として出力する。
+//! 寸法を明示してレイアウトシフトを防ぎ、モバイルでは縮小版を読み込ませる。
+//! 縮小版は元画像より小さくなる場合だけ使う（WebPをJPEGにすると大きくなることがある）。
+
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+use anyhow::Result;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{ImageDecoder, ImageFormat, ImageReader};
+use maud::html;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::cache::BuildCache;
+use crate::models::ResponsiveImage;
+
+/// 縮小版の幅（本文画像の表示幅 `min(560px, 100%)` の1x/2x）
+const VARIANT_WIDTHS: [u32; 2] = [560, 1120];
+
+/// `.main-content img` の表示幅に合わせたsizes属性
+const SIZES: &str = "(max-width: 560px) 100vw, 560px";
+
+const JPEG_QUALITY: u8 = 80;
+
+const IMAGE_EXTENSIONS: [&str; 4] = ["webp", "png", "jpg", "jpeg"];
+
+impl ResponsiveImage {
+    /// 縮小版のURL（`/content/foo/01.webp` → `/content/foo/01-560w.jpg`）
+    pub fn variant_url(&self, width: u32) -> String {
+        let stem = self
+            .url
+            .rsplit_once('.')
+            .map(|(stem, _)| stem)
+            .unwrap_or(&self.url);
+        format!("{stem}-{width}w.{}", self.variant_extension())
+    }
+
+    fn variant_extension(&self) -> &'static str {
+        if self.has_alpha { "png" } else { "jpg" }
+    }
+}
+
+/// `static/` 以下の画像を調べる
+///
+/// 縮小版はエンコードした結果が元画像より小さい幅だけを残す（エンコード結果はキャッシュする）。
+/// 外部URLや存在しないファイル・画像以外は `None`（リンク切れは `links` で報告する）
+pub fn inspect(static_dir: &Path, url: &str, build_cache: &BuildCache) -> Option<ResponsiveImage> {
+    let relative_path = url
+        .strip_prefix('/')
+        .filter(|path| !path.starts_with('/'))?;
+    let extension = relative_path.rsplit_once('.')?.1.to_ascii_lowercase();
+    if !IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+        return None;
+    }
+
+    let source = fs::read(static_dir.join(relative_path)).ok()?;
+    let decoder = ImageReader::new(Cursor::new(&source))
+        .with_guessed_format()
+        .ok()?
+        .into_decoder()
+        .ok()?;
+    let (width, height) = decoder.dimensions();
+
+    let mut image = ResponsiveImage {
+        url: url.to_string(),
+        width,
+        height,
+        has_alpha: decoder.color_type().has_alpha(),
+        variant_widths: Vec::new(),
+    };
+    image.variant_widths = smaller_variant_widths(&source, &image, build_cache);
+    Some(image)
+}
+
+/// 元画像より小さくなる縮小版の幅
+fn smaller_variant_widths(
+    source: &[u8],
+    image: &ResponsiveImage,
+    build_cache: &BuildCache,
+) -> Vec<u32> {
+    VARIANT_WIDTHS
+        .into_iter()
+        .filter(|&width| width < image.width)
+        .filter(|&width| {
+            build_cache
+                .image_variant(source, width, image.variant_extension(), || {
+                    encode_variant(source, image, width)
+                })
+                .is_ok_and(|variant| variant.len() < source.len())
+        })
+        .collect()
+}
+
+/// `<picture>` のHTMLを生成する
+pub fn picture_html(image: &ResponsiveImage, alt: &str, title: &str) -> String {
+    let srcset = image
+        .variant_widths
+        .iter()
+        .map(|&width| format!("{} {width}w", image.variant_url(width)))
+        .chain([format!("{} {}w", image.url, image.width)])
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    html! {
+        picture {
+            @if !image.variant_widths.is_empty() {
+                source srcset=(srcset) sizes=(SIZES);
+            }
+            img src=(image.url) alt=(alt) title=[(!title.is_empty()).then_some(title)]
+                width=(image.width) height=(image.height) loading="lazy" decoding="async";
+        }
+    }
+    .into_string()
+}
+
+fn encode_variant(source: &[u8], image: &ResponsiveImage, width: u32) -> Result<Vec<u8>> {
+    let height = (u64::from(image.height) * u64::from(width) / u64::from(image.width)).max(1);
+    let resized = image::load_from_memory(source)?.resize_exact(
+        width,
+        u32::try_from(height)?,
+        FilterType::Lanczos3,
+    );
+
+    let mut encoded = Cursor::new(Vec::new());
+    if image.has_alpha {
+        resized.write_to(&mut encoded, ImageFormat::Png)?;
+    } else {
+        JpegEncoder::new_with_quality(&mut encoded, JPEG_QUALITY)
+            .encode_image(&resized.to_rgb8())?;
+    }
+    Ok(encoded.into_inner())
+}
+
+/// 記事で使われている画像の縮小版を `dist/` に書き出す
+pub fn write_variants(
+    images: &[&ResponsiveImage],
+    static_dir: &Path,
+    dist_dir: &Path,
+    build_cache: &BuildCache,
+) -> Result<()> {
+    let jobs: Vec<(&ResponsiveImage, u32)> = images
+        .iter()
+        .flat_map(|image| {
+            image
+                .variant_widths
+                .iter()
+                .map(move |&width| (*image, width))
+        })
+        .collect();
+
+    jobs.into_par_iter()
+        .map(|(image, width)| {
+            let relative_path = image.url.trim_start_matches('/');
+            let source = fs::read(static_dir.join(relative_path))?;
+            let dest = dist_dir.join(image.variant_url(width).trim_start_matches('/'));
+            let variant =
+                build_cache.image_variant(&source, width, image.variant_extension(), || {
+                    encode_variant(&source, image, width)
+                })?;
+            fs::write(dest, variant)?;
+            Ok(())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variant_url() {
+        let mut image = ResponsiveImage {
+            url: "/content/foo/01-box.webp".to_string(),
+            width: 1600,
+            height: 1200,
+            has_alpha: false,
+            variant_widths: vec![560, 1120],
+        };
+        assert_eq!(image.variant_url(560), "/content/foo/01-box-560w.jpg");
+        image.has_alpha = true;
+        assert_eq!(image.variant_url(1120), "/content/foo/01-box-1120w.png");
+    }
+
+    /// ノイズの多い画像（圧縮が効きにくい）
+    fn noise(width: u32, height: u32) -> image::RgbImage {
+        image::RgbImage::from_fn(width, height, |x, y| {
+            let n = (x.wrapping_mul(2_654_435_761) ^ y.wrapping_mul(40_503)) as u8;
+            image::Rgb([n, n.wrapping_mul(3), n.wrapping_mul(7)])
+        })
+    }
+
+    #[test]
+    fn test_variants_must_be_smaller_than_source() {
+        let build_cache = BuildCache::disabled();
+        let image = ResponsiveImage {
+            url: "/content/foo/01.jpg".to_string(),
+            width: 1600,
+            height: 200,
+            has_alpha: false,
+            variant_widths: Vec::new(),
+        };
+
+        let mut png = Cursor::new(Vec::new());
+        noise(1600, 200)
+            .write_to(&mut png, ImageFormat::Png)
+            .unwrap();
+        assert_eq!(
+            smaller_variant_widths(png.get_ref(), &image, &build_cache),
+            vec![560, 1120]
+        );
+
+        // 低画質で圧縮された元画像は、品質80で再エンコードした1120w版の方が大きくなる
+        let mut jpeg = Cursor::new(Vec::new());
+        JpegEncoder::new_with_quality(&mut jpeg, 10)
+            .encode_image(&noise(1600, 200))
+            .unwrap();
+        assert_eq!(
+            smaller_variant_widths(jpeg.get_ref(), &image, &build_cache),
+            vec![560]
+        );
+    }
+}
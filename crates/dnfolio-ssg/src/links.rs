@@ -0,0 +1,197 @@
+//! 内部リンク・アンカーの検証
+//!
+//! 記事本文から集めたリンク・画像の参照先を、ビルドで生成されるURL、
+//! 記事の見出しID、`static/` 以下のファイルと突き合わせてリンク切れを検出する。
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::config::SiteConfig;
+use crate::models::Article;
+
+/// 検証対象になりうる参照先か（相対パスや `mailto:` 等は対象外）
+///
+/// サイトの絶対URL（`https://dnfolio.me/...`）は `base_url` が分からない
+/// パース時点では外部URLと区別できないため、検証時に振り分ける
+pub fn is_internal_candidate(url: &str) -> bool {
+    (url.starts_with('/') && !url.starts_with("//"))
+        || url.starts_with('#')
+        || url.starts_with("http://")
+        || url.starts_with("https://")
+}
+
+#[derive(Debug)]
+pub struct BrokenLink {
+    pub path: PathBuf,
+    pub line: usize,
+    pub url: String,
+    pub reason: String,
+}
+
+impl fmt::Display for BrokenLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: warning: リンク切れ {} ({})",
+            self.path.display(),
+            self.line,
+            self.url,
+            self.reason
+        )
+    }
+}
+
+/// サイト内の参照先（パスとフラグメント）
+#[derive(Debug, PartialEq)]
+struct Target<'a> {
+    /// 空文字列は同じページ内へのリンク
+    path: &'a str,
+    fragment: Option<&'a str>,
+}
+
+/// 参照先をサイト内のパスとフラグメントに分解する（外部URLなら `None`）
+fn split_target<'a>(base_url: &str, url: &'a str) -> Option<Target<'a>> {
+    let url = match url.strip_prefix(base_url) {
+        Some("") => "/",
+        Some(rest) if rest.starts_with(['/', '#', '?']) => rest,
+        Some(_) => return None,
+        None if url.starts_with("http://") || url.starts_with("https://") => return None,
+        None => url,
+    };
+
+    let (rest, fragment) = match url.split_once('#') {
+        Some((rest, fragment)) => (rest, Some(fragment).filter(|f| !f.is_empty())),
+        None => (url, None),
+    };
+    let path = rest.split('?').next().unwrap_or_default();
+    Some(Target { path, fragment })
+}
+
+/// 末尾のセグメントに拡張子があればファイルへの参照とみなす
+fn is_file_path(path: &str) -> bool {
+    path.rsplit('/')
+        .next()
+        .is_some_and(|name| name.contains('.'))
+}
+
+/// ページURLを末尾スラッシュ付きに揃える（`/posts/foo` → `/posts/foo/`）
+fn normalize_page_path(path: &str) -> String {
+    if path.ends_with('/') {
+        path.to_string()
+    } else {
+        format!("{path}/")
+    }
+}
+
+/// 全記事のリンクを検証する
+///
+/// `generated_urls` には記事以外に生成されるページ・ファイルのURL
+/// （トップ、固定ページ、タグページ、フィード等）を渡す
+pub fn validate(
+    site: &SiteConfig,
+    articles: &[Article],
+    generated_urls: &[String],
+    static_dir: &Path,
+) -> Vec<BrokenLink> {
+    let anchors: HashMap<String, HashSet<&str>> = articles
+        .iter()
+        .map(|article| {
+            (
+                article.relative_url.to_string_lossy().into_owned(),
+                article.headings.iter().map(|h| h.id.as_str()).collect(),
+            )
+        })
+        .collect();
+    let generated: HashSet<&str> = generated_urls.iter().map(String::as_str).collect();
+
+    let mut broken = Vec::new();
+    for article in articles {
+        let own_url = article.relative_url.to_string_lossy();
+        for link in &article.links {
+            let Some(target) = split_target(&site.base_url, &link.url) else {
+                continue;
+            };
+
+            let reason = if target.path.is_empty() {
+                target
+                    .fragment
+                    .filter(|fragment| !anchors[own_url.as_ref()].contains(fragment))
+                    .map(|fragment| format!("見出し #{fragment} がこの記事にありません"))
+            } else if is_file_path(target.path) {
+                let exists = generated.contains(target.path)
+                    || static_dir
+                        .join(target.path.trim_start_matches('/'))
+                        .is_file();
+                (!exists).then(|| format!("static{} が存在しません", target.path))
+            } else {
+                let page_path = normalize_page_path(target.path);
+                match anchors.get(&page_path) {
+                    Some(ids) => target
+                        .fragment
+                        .filter(|fragment| !ids.contains(fragment))
+                        .map(|fragment| format!("見出し #{fragment} が {page_path} にありません")),
+                    None if generated.contains(page_path.as_str()) => None,
+                    None => Some(format!("{page_path} は生成されません")),
+                }
+            };
+
+            if let Some(reason) = reason {
+                broken.push(BrokenLink {
+                    path: article.source_path.clone(),
+                    line: link.line,
+                    url: link.url.clone(),
+                    reason,
+                });
+            }
+        }
+    }
+
+    broken.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+    broken
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE_URL: &str = "https://dnfolio.me";
+
+    #[test]
+    fn test_split_target() {
+        assert_eq!(
+            split_target(BASE_URL, "/posts/foo/#bar"),
+            Some(Target {
+                path: "/posts/foo/",
+                fragment: Some("bar"),
+            })
+        );
+        assert_eq!(
+            split_target(BASE_URL, "https://dnfolio.me"),
+            Some(Target {
+                path: "/",
+                fragment: None,
+            })
+        );
+        assert_eq!(
+            split_target(BASE_URL, "#heading"),
+            Some(Target {
+                path: "",
+                fragment: Some("heading"),
+            })
+        );
+        assert_eq!(split_target(BASE_URL, "https://example.com/"), None);
+        assert_eq!(
+            split_target(BASE_URL, "https://dnfolio.me.example.com/"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_is_file_path() {
+        assert!(is_file_path("/content/foo/01.webp"));
+        assert!(is_file_path("/rss.xml"));
+        assert!(!is_file_path("/posts/foo/"));
+        assert!(!is_file_path("/posts/foo"));
+    }
+}
@@ -1,46 +1,169 @@
 mod build;
+mod cache;
+mod check;
+mod config;
 mod dates;
+mod feeds;
+mod front_matter;
+mod highlight;
+mod history;
+mod i18n;
+mod images;
+mod links;
+mod math;
 mod models;
 mod ogp;
+mod publish;
+mod reading;
 mod redirects;
-mod rss;
+mod related;
+mod scaffold;
+mod series;
 mod serve;
+mod shortcodes;
 mod sitemap;
 mod structured_data;
+mod tags;
 mod templates;
+mod watch;
 
-use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+use chrono::{DateTime, FixedOffset};
+use clap::{Args, Parser, Subcommand};
+use tokio::sync::broadcast;
+
+use crate::config::SiteConfig;
 
 #[derive(Parser)]
 #[command(name = "dnfolio", version, about)]
 struct Cli {
+    /// Path to site configuration file
+    #[arg(long, global = true, default_value = config::DEFAULT_CONFIG_PATH)]
+    config: PathBuf,
     #[command(subcommand)]
     command: Commands,
 }
 
+#[derive(Args)]
+struct BuildArgs {
+    /// Ignore and do not update the incremental build cache
+    #[arg(long)]
+    no_cache: bool,
+    /// Fail the build on broken internal links
+    #[arg(long)]
+    strict: bool,
+    /// Treat this date (YYYY-MM-DD or RFC 3339) as now when deciding scheduled posts
+    #[arg(long, value_parser = parse_now)]
+    now: Option<DateTime<FixedOffset>>,
+}
+
+fn parse_now(value: &str) -> Result<DateTime<FixedOffset>, String> {
+    dates::parse_datetime(value)
+        .ok_or_else(|| format!("invalid date \"{value}\" (expected YYYY-MM-DD or RFC 3339)"))
+}
+
+impl BuildArgs {
+    fn options(&self) -> build::BuildOptions {
+        build::BuildOptions {
+            use_cache: !self.no_cache,
+            strict: self.strict,
+            drafts: false,
+            now: self.now,
+        }
+    }
+}
+
+#[derive(Args)]
+struct ServeArgs {
+    #[command(flatten)]
+    build: BuildArgs,
+    /// Render draft and scheduled posts as previews (noindex, not listed)
+    #[arg(long)]
+    drafts: bool,
+}
+
+impl ServeArgs {
+    fn options(&self) -> build::BuildOptions {
+        build::BuildOptions {
+            drafts: self.drafts,
+            ..self.build.options()
+        }
+    }
+}
+
+#[derive(Args)]
+struct CheckArgs {
+    /// Treat this date (YYYY-MM-DD or RFC 3339) as now when deciding scheduled posts
+    #[arg(long, value_parser = parse_now)]
+    now: Option<DateTime<FixedOffset>>,
+}
+
+#[derive(Args)]
+struct NewArgs {
+    /// Title of the new post
+    title: String,
+    /// URL slug (defaults to the title if it is ASCII, otherwise post-YYYYMMDD)
+    #[arg(long)]
+    slug: Option<String>,
+    /// Comma-separated tags
+    #[arg(long, value_delimiter = ',')]
+    tags: Vec<String>,
+    /// Language of the post (defaults to the site language)
+    #[arg(long)]
+    lang: Option<String>,
+    /// Also create static/content/<slug>/ for images
+    #[arg(long)]
+    assets: bool,
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Build to static files
-    Build,
-    /// Starting local develop server
-    Serve,
+    Build(BuildArgs),
+    /// Starting local develop server (rebuilds and live-reloads on change)
+    Serve(ServeArgs),
+    /// Lint content without writing dist/
+    Check(CheckArgs),
+    /// Create a new draft post in content/
+    New(NewArgs),
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
+    let site_config = SiteConfig::load(&cli.config)?;
 
     match cli.command {
-        Commands::Build => {
+        Commands::Build(args) => {
             println!("Building static files ...");
-            build::run().await?;
+            build::run(&site_config, &args.options()).await?;
             println!("Build finished!");
         }
-        Commands::Serve => {
+        Commands::Serve(args) => {
             println!("Starting development server ...");
-            build::run().await?;
+            let options = args.options();
+            build::run(&site_config, &options).await?;
             println!("Build finished!");
-            serve::run().await?;
+
+            let (reload_tx, _) = broadcast::channel(16);
+            let _watcher = watch::spawn(site_config, options, reload_tx.clone())?;
+            serve::run(reload_tx).await?;
+        }
+        Commands::Check(args) => {
+            check::run(&site_config, args.now.unwrap_or_else(dates::build_time))?;
+        }
+        Commands::New(args) => {
+            scaffold::run(
+                &site_config,
+                &scaffold::NewPost {
+                    title: args.title,
+                    slug: args.slug,
+                    tags: args.tags,
+                    language: args.lang,
+                    with_assets: args.assets,
+                },
+            )?;
         }
     }
     Ok(())
@@ -0,0 +1,804 @@
+//! LaTeX数式のMathML変換
+//!
+//! pulldown-cmarkの `$...$` / `$$...$$` をビルド時にMathMLへ変換する。
+//! クライアント側のJavaScript（KaTeX等）を使わず、CSPもそのままで表示できる。
+//! ブログで使う範囲のサブセット（分数・根号・添字・ギリシャ文字・演算子・
+//! 行列/cases環境・アクセント・書体指定）に対応する。
+
+use std::fmt::Write;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// `\alpha` 等のコマンド（記号1文字のコマンド `\,` `\{` も含む）
+    Command(String),
+    Letter(char),
+    Number(String),
+    Symbol(char),
+    BeginGroup,
+    EndGroup,
+    Superscript,
+    Subscript,
+    Ampersand,
+    /// 連続する空白（数式中では無視し、`\text` 等の中でだけ使う）
+    Space,
+}
+
+fn tokenize(source: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        let token = match c {
+            '\\' => {
+                let mut name = String::new();
+                while let Some(&next) = chars.peek() {
+                    if !next.is_ascii_alphabetic() {
+                        break;
+                    }
+                    name.push(next);
+                    chars.next();
+                }
+                if name.is_empty()
+                    && let Some(symbol) = chars.next()
+                {
+                    name.push(symbol);
+                }
+                Token::Command(name)
+            }
+            '{' => Token::BeginGroup,
+            '}' => Token::EndGroup,
+            '^' => Token::Superscript,
+            '_' => Token::Subscript,
+            '&' => Token::Ampersand,
+            c if c.is_whitespace() => {
+                while chars.next_if(|next| next.is_whitespace()).is_some() {}
+                Token::Space
+            }
+            c if c.is_ascii_digit() => {
+                let mut number = c.to_string();
+                while let Some(&next) = chars.peek() {
+                    if !(next.is_ascii_digit() || next == '.') {
+                        break;
+                    }
+                    number.push(next);
+                    chars.next();
+                }
+                Token::Number(number)
+            }
+            c if c.is_alphabetic() => Token::Letter(c),
+            c => Token::Symbol(c),
+        };
+        tokens.push(token);
+    }
+    tokens
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// 記号コマンド（`mi` として出力するもの）
+fn identifier_command(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "alpha" => "α",
+        "beta" => "β",
+        "gamma" => "γ",
+        "delta" => "δ",
+        "epsilon" => "ϵ",
+        "varepsilon" => "ε",
+        "zeta" => "ζ",
+        "eta" => "η",
+        "theta" => "θ",
+        "vartheta" => "ϑ",
+        "iota" => "ι",
+        "kappa" => "κ",
+        "lambda" => "λ",
+        "mu" => "μ",
+        "nu" => "ν",
+        "xi" => "ξ",
+        "pi" => "π",
+        "varpi" => "ϖ",
+        "rho" => "ρ",
+        "varrho" => "ϱ",
+        "sigma" => "σ",
+        "varsigma" => "ς",
+        "tau" => "τ",
+        "upsilon" => "υ",
+        "phi" => "ϕ",
+        "varphi" => "φ",
+        "chi" => "χ",
+        "psi" => "ψ",
+        "omega" => "ω",
+        "infty" => "∞",
+        "emptyset" | "varnothing" => "∅",
+        "ell" => "ℓ",
+        "hbar" => "ℏ",
+        "partial" => "∂",
+        "nabla" => "∇",
+        "aleph" => "ℵ",
+        _ => return None,
+    })
+}
+
+/// 大文字のギリシャ文字（立体で出力する）
+fn upright_identifier_command(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "Gamma" => "Γ",
+        "Delta" => "Δ",
+        "Theta" => "Θ",
+        "Lambda" => "Λ",
+        "Xi" => "Ξ",
+        "Pi" => "Π",
+        "Sigma" => "Σ",
+        "Upsilon" => "Υ",
+        "Phi" => "Φ",
+        "Psi" => "Ψ",
+        "Omega" => "Ω",
+        _ => return None,
+    })
+}
+
+/// 演算子・関係・矢印・括弧（`mo` として出力するもの）
+fn operator_command(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "times" => "×",
+        "cdot" => "⋅",
+        "div" => "÷",
+        "pm" => "±",
+        "mp" => "∓",
+        "ast" => "∗",
+        "star" => "⋆",
+        "circ" => "∘",
+        "bullet" => "∙",
+        "leq" | "le" => "≤",
+        "geq" | "ge" => "≥",
+        "neq" | "ne" => "≠",
+        "approx" => "≈",
+        "equiv" => "≡",
+        "sim" => "∼",
+        "simeq" => "≃",
+        "cong" => "≅",
+        "propto" => "∝",
+        "ll" => "≪",
+        "gg" => "≫",
+        "in" => "∈",
+        "notin" => "∉",
+        "ni" => "∋",
+        "subset" => "⊂",
+        "supset" => "⊃",
+        "subseteq" => "⊆",
+        "supseteq" => "⊇",
+        "cup" => "∪",
+        "cap" => "∩",
+        "setminus" => "∖",
+        "forall" => "∀",
+        "exists" => "∃",
+        "neg" | "lnot" => "¬",
+        "land" | "wedge" => "∧",
+        "lor" | "vee" => "∨",
+        "oplus" => "⊕",
+        "otimes" => "⊗",
+        "to" | "rightarrow" => "→",
+        "leftarrow" | "gets" => "←",
+        "leftrightarrow" => "↔",
+        "Rightarrow" => "⇒",
+        "Leftarrow" => "⇐",
+        "Leftrightarrow" => "⇔",
+        "implies" => "⟹",
+        "iff" => "⟺",
+        "mapsto" => "↦",
+        "uparrow" => "↑",
+        "downarrow" => "↓",
+        "ldots" | "dots" => "…",
+        "cdots" => "⋯",
+        "vdots" => "⋮",
+        "ddots" => "⋱",
+        "mid" => "∣",
+        "parallel" => "∥",
+        "perp" => "⊥",
+        "angle" => "∠",
+        "langle" => "⟨",
+        "rangle" => "⟩",
+        "lfloor" => "⌊",
+        "rfloor" => "⌋",
+        "lceil" => "⌈",
+        "rceil" => "⌉",
+        "vert" | "lvert" | "rvert" => "|",
+        "Vert" | "lVert" | "rVert" => "‖",
+        "colon" => ":",
+        "backslash" => "\\",
+        "int" => "∫",
+        "iint" => "∬",
+        "iiint" => "∭",
+        "oint" => "∮",
+        "{" => "{",
+        "}" => "}",
+        "|" => "‖",
+        _ => return None,
+    })
+}
+
+/// 表示数式で添字を上下に置く大型演算子
+fn large_operator_command(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "sum" => "∑",
+        "prod" => "∏",
+        "coprod" => "∐",
+        "bigcup" => "⋃",
+        "bigcap" => "⋂",
+        "bigoplus" => "⨁",
+        "bigotimes" => "⨂",
+        _ => return None,
+    })
+}
+
+/// 関数名（立体で出力する）と、表示数式で添字を上下に置くかどうか
+fn function_command(name: &str) -> Option<bool> {
+    match name {
+        "sin" | "cos" | "tan" | "cot" | "sec" | "csc" | "arcsin" | "arccos" | "arctan" | "sinh"
+        | "cosh" | "tanh" | "log" | "ln" | "lg" | "exp" | "dim" | "ker" | "deg" | "arg" | "hom" => {
+            Some(false)
+        }
+        "lim" | "liminf" | "limsup" | "min" | "max" | "sup" | "inf" | "det" | "gcd" | "Pr" => {
+            Some(true)
+        }
+        _ => None,
+    }
+}
+
+fn space_command(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "," => "0.1667em",
+        ":" | ">" => "0.2222em",
+        ";" => "0.2778em",
+        " " => "0.25em",
+        "quad" => "1em",
+        "qquad" => "2em",
+        "!" => "-0.1667em",
+        _ => return None,
+    })
+}
+
+/// アクセント記号（`\hat{x}` 等）
+fn accent_command(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "hat" | "widehat" => "^",
+        "bar" | "overline" => "‾",
+        "vec" | "overrightarrow" => "→",
+        "dot" => "˙",
+        "ddot" => "¨",
+        "tilde" | "widetilde" => "~",
+        _ => return None,
+    })
+}
+
+/// 書体指定をUnicodeの数学用英数字に変換する
+fn styled_char(variant: &str, c: char) -> char {
+    let special = match (variant, c) {
+        ("mathbb", 'C') => Some('ℂ'),
+        ("mathbb", 'H') => Some('ℍ'),
+        ("mathbb", 'N') => Some('ℕ'),
+        ("mathbb", 'P') => Some('ℙ'),
+        ("mathbb", 'Q') => Some('ℚ'),
+        ("mathbb", 'R') => Some('ℝ'),
+        ("mathbb", 'Z') => Some('ℤ'),
+        ("mathcal", 'B') => Some('ℬ'),
+        ("mathcal", 'E') => Some('ℰ'),
+        ("mathcal", 'F') => Some('ℱ'),
+        ("mathcal", 'H') => Some('ℋ'),
+        ("mathcal", 'I') => Some('ℐ'),
+        ("mathcal", 'L') => Some('ℒ'),
+        ("mathcal", 'M') => Some('ℳ'),
+        ("mathcal", 'R') => Some('ℛ'),
+        _ => None,
+    };
+    if let Some(special) = special {
+        return special;
+    }
+
+    let (upper, lower) = match variant {
+        "mathbf" => (0x1D400, Some(0x1D41A)),
+        "mathbb" => (0x1D538, Some(0x1D552)),
+        "mathcal" => (0x1D49C, None),
+        _ => return c,
+    };
+    let code = match c {
+        'A'..='Z' => upper + (c as u32 - 'A' as u32),
+        'a'..='z' => match lower {
+            Some(lower) => lower + (c as u32 - 'a' as u32),
+            None => return c,
+        },
+        _ => return c,
+    };
+    char::from_u32(code).unwrap_or(c)
+}
+
+/// 行の終端条件
+#[derive(Clone, Copy, PartialEq)]
+enum Stop {
+    End,
+    Group,
+    Right,
+    /// 環境内のセル（`&` `\\` `\end` で終わる）
+    Cell,
+}
+
+struct Converter {
+    tokens: Vec<Token>,
+    pos: usize,
+    display: bool,
+    errors: Vec<String>,
+}
+
+impl Converter {
+    /// 空白を飛ばした次のトークンの位置
+    fn next_index(&self) -> usize {
+        let mut index = self.pos;
+        while self.tokens.get(index) == Some(&Token::Space) {
+            index += 1;
+        }
+        index
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next_index())
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let index = self.next_index();
+        self.pos = index + 1;
+        self.tokens.get(index).cloned()
+    }
+
+    fn is_stop(&self, stop: Stop) -> bool {
+        match (self.peek(), stop) {
+            (None, _) => true,
+            (Some(Token::EndGroup), Stop::Group | Stop::Cell) => true,
+            (Some(Token::Command(name)), Stop::Right) => name == "right",
+            (Some(Token::Ampersand), Stop::Cell) => true,
+            (Some(Token::Command(name)), Stop::Cell) => name == "\\" || name == "end",
+            _ => false,
+        }
+    }
+
+    fn row(&mut self, stop: Stop) -> String {
+        let mut nodes = String::new();
+        while !self.is_stop(stop) {
+            if let Some(node) = self.atom_with_scripts() {
+                nodes.push_str(&node);
+            }
+        }
+        nodes
+    }
+
+    fn mrow(nodes: String) -> String {
+        format!("<mrow>{nodes}</mrow>")
+    }
+
+    /// `{...}` または1トークンを引数として読む
+    fn argument(&mut self) -> String {
+        match self.peek() {
+            Some(Token::BeginGroup) => {
+                self.next();
+                let nodes = self.row(Stop::Group);
+                self.expect_end_group();
+                Self::mrow(nodes)
+            }
+            // `x^23` は `x^{2}3` と同じ（数字は1文字だけ取る）
+            Some(Token::Number(number)) if number.chars().count() > 1 => {
+                let number = number.clone();
+                let mut chars = number.chars();
+                let first = chars.next().unwrap_or_default();
+                let index = self.next_index();
+                self.tokens[index] = Token::Number(chars.collect());
+                format!("<mn>{first}</mn>")
+            }
+            _ => self.atom().unwrap_or_else(|| Self::mrow(String::new())),
+        }
+    }
+
+    /// `{...}` の中身を空白も含めて文字列のまま読む（`\text` や環境名用）
+    fn raw_argument(&mut self, source: &str) -> String {
+        if self.peek() != Some(&Token::BeginGroup) {
+            self.errors
+                .push(format!("`\\{source}` の引数には {{}} が必要です"));
+            return String::new();
+        }
+        self.next();
+        let mut text = String::new();
+        let mut depth = 0;
+        while let Some(token) = self.tokens.get(self.pos).cloned() {
+            self.pos += 1;
+            match token {
+                Token::BeginGroup => depth += 1,
+                Token::EndGroup if depth == 0 => break,
+                Token::EndGroup => depth -= 1,
+                Token::Command(name) if name == " " => text.push(' '),
+                Token::Space => text.push(' '),
+                Token::Command(name) => {
+                    text.push('\\');
+                    text.push_str(&name);
+                }
+                Token::Letter(c) | Token::Symbol(c) => text.push(c),
+                Token::Number(number) => text.push_str(&number),
+                Token::Superscript => text.push('^'),
+                Token::Subscript => text.push('_'),
+                Token::Ampersand => text.push('&'),
+            }
+        }
+        text
+    }
+
+    fn expect_end_group(&mut self) {
+        if self.peek() == Some(&Token::EndGroup) {
+            self.next();
+        } else {
+            self.errors.push("`}` が足りません".to_string());
+        }
+    }
+
+    /// 要素を1つ読み、後続の `^` `_` `'` を添字として付ける
+    fn atom_with_scripts(&mut self) -> Option<String> {
+        let (base, movable_limits) = match self.peek() {
+            Some(Token::Superscript | Token::Subscript) => (Self::mrow(String::new()), false),
+            Some(Token::Command(name)) => {
+                let movable =
+                    large_operator_command(name).is_some() || function_command(name) == Some(true);
+                (self.atom()?, movable)
+            }
+            _ => (self.atom()?, false),
+        };
+
+        let mut subscript = None;
+        let mut superscript = None;
+        loop {
+            match self.peek() {
+                Some(Token::Subscript) if subscript.is_none() => {
+                    self.next();
+                    subscript = Some(self.argument());
+                }
+                Some(Token::Superscript) if superscript.is_none() => {
+                    self.next();
+                    superscript = Some(self.argument());
+                }
+                Some(Token::Symbol('\'')) if superscript.is_none() => {
+                    self.next();
+                    superscript = Some("<mo>′</mo>".to_string());
+                }
+                _ => break,
+            }
+        }
+
+        let limits = movable_limits && self.display;
+        Some(match (subscript, superscript) {
+            (None, None) => base,
+            (Some(sub), None) if limits => format!("<munder>{base}{sub}</munder>"),
+            (Some(sub), None) => format!("<msub>{base}{sub}</msub>"),
+            (None, Some(sup)) if limits => format!("<mover>{base}{sup}</mover>"),
+            (None, Some(sup)) => format!("<msup>{base}{sup}</msup>"),
+            (Some(sub), Some(sup)) if limits => {
+                format!("<munderover>{base}{sub}{sup}</munderover>")
+            }
+            (Some(sub), Some(sup)) => format!("<msubsup>{base}{sub}{sup}</msubsup>"),
+        })
+    }
+
+    fn atom(&mut self) -> Option<String> {
+        let token = self.next()?;
+        Some(match token {
+            Token::Number(number) => format!("<mn>{number}</mn>"),
+            Token::Letter(c) => format!("<mi>{c}</mi>"),
+            Token::Symbol(c) => {
+                let symbol = match c {
+                    '-' => "−".to_string(),
+                    '*' => "∗".to_string(),
+                    '\'' => "′".to_string(),
+                    c => escape(&c.to_string()),
+                };
+                format!("<mo>{symbol}</mo>")
+            }
+            Token::BeginGroup => {
+                let nodes = self.row(Stop::Group);
+                self.expect_end_group();
+                Self::mrow(nodes)
+            }
+            Token::EndGroup => {
+                self.errors
+                    .push("対応する `{` のない `}` があります".to_string());
+                return None;
+            }
+            // 環境の外の `&` は無視する
+            Token::Ampersand => return None,
+            Token::Superscript | Token::Subscript | Token::Space => return None,
+            Token::Command(name) => self.command(&name),
+        })
+    }
+
+    fn command(&mut self, name: &str) -> String {
+        if let Some(symbol) = identifier_command(name) {
+            return format!("<mi>{symbol}</mi>");
+        }
+        if let Some(symbol) = upright_identifier_command(name) {
+            return format!(r#"<mi mathvariant="normal">{symbol}</mi>"#);
+        }
+        if let Some(symbol) = operator_command(name) {
+            return format!("<mo>{}</mo>", escape(symbol));
+        }
+        if let Some(symbol) = large_operator_command(name) {
+            return format!("<mo>{symbol}</mo>");
+        }
+        if function_command(name).is_some() {
+            return format!("<mi>{name}</mi>");
+        }
+        if let Some(width) = space_command(name) {
+            return format!(r#"<mspace width="{width}"></mspace>"#);
+        }
+        if let Some(accent) = accent_command(name) {
+            let base = self.argument();
+            return format!(r#"<mover accent="true">{base}<mo>{accent}</mo></mover>"#);
+        }
+
+        match name {
+            "frac" | "dfrac" | "tfrac" => {
+                let numerator = self.argument();
+                let denominator = self.argument();
+                format!("<mfrac>{numerator}{denominator}</mfrac>")
+            }
+            "sqrt" => {
+                if self.peek() == Some(&Token::Symbol('[')) {
+                    self.next();
+                    let mut index = String::new();
+                    while !matches!(self.peek(), None | Some(Token::Symbol(']'))) {
+                        if let Some(node) = self.atom_with_scripts() {
+                            index.push_str(&node);
+                        }
+                    }
+                    self.next();
+                    let radicand = self.argument();
+                    format!("<mroot>{radicand}{}</mroot>", Self::mrow(index))
+                } else {
+                    format!("<msqrt>{}</msqrt>", self.argument())
+                }
+            }
+            "text" | "textrm" | "mbox" => {
+                format!("<mtext>{}</mtext>", escape(&self.raw_argument(name)))
+            }
+            "mathrm" | "operatorname" => {
+                format!(
+                    r#"<mi mathvariant="normal">{}</mi>"#,
+                    escape(&self.raw_argument(name))
+                )
+            }
+            "mathbf" | "mathbb" | "mathcal" => {
+                let text: String = self
+                    .raw_argument(name)
+                    .chars()
+                    .filter(|c| !c.is_whitespace())
+                    .map(|c| styled_char(name, c))
+                    .collect();
+                format!("<mi>{}</mi>", escape(&text))
+            }
+            "left" => {
+                let open = self.delimiter();
+                let inner = self.row(Stop::Right);
+                // `\right` を読み飛ばす
+                self.next();
+                let close = self.delimiter();
+                format!(
+                    "<mrow>{}{inner}{}</mrow>",
+                    Self::fence(&open),
+                    Self::fence(&close)
+                )
+            }
+            "right" => {
+                self.errors
+                    .push("対応する `\\left` のない `\\right` があります".to_string());
+                self.delimiter();
+                String::new()
+            }
+            "begin" => self.environment(),
+            // 環境の外の改行は無視する
+            "\\" => String::new(),
+            "#" | "%" | "&" | "_" | "$" => format!("<mi>{}</mi>", escape(name)),
+            _ => {
+                self.errors
+                    .push(format!("未対応のコマンド `\\{name}` です"));
+                format!("<merror><mtext>\\{}</mtext></merror>", escape(name))
+            }
+        }
+    }
+
+    /// `\left` / `\right` の直後の区切り文字（`.` は空）
+    fn delimiter(&mut self) -> String {
+        match self.next() {
+            Some(Token::Symbol('.')) | None => String::new(),
+            Some(Token::Symbol(c)) => c.to_string(),
+            Some(Token::Command(name)) => operator_command(&name)
+                .map(str::to_string)
+                .unwrap_or_else(|| {
+                    self.errors
+                        .push(format!("区切り文字 `\\{name}` は使えません"));
+                    String::new()
+                }),
+            Some(_) => {
+                self.errors
+                    .push("`\\left` / `\\right` の後に区切り文字が必要です".to_string());
+                String::new()
+            }
+        }
+    }
+
+    fn fence(delimiter: &str) -> String {
+        if delimiter.is_empty() {
+            String::new()
+        } else {
+            format!(r#"<mo fence="true">{}</mo>"#, escape(delimiter))
+        }
+    }
+
+    /// `\begin{name} ... \end{name}`（行列・cases・aligned）
+    fn environment(&mut self) -> String {
+        let name = self.raw_argument("begin").trim().to_string();
+        let (open, close, column_align) = match name.as_str() {
+            "matrix" => ("", "", None),
+            "pmatrix" => ("(", ")", None),
+            "bmatrix" => ("[", "]", None),
+            "Bmatrix" => ("{", "}", None),
+            "vmatrix" => ("|", "|", None),
+            "Vmatrix" => ("‖", "‖", None),
+            "cases" => ("{", "", Some("left left")),
+            "aligned" | "align" | "align*" | "split" => ("", "", Some("right left")),
+            _ => {
+                self.errors.push(format!("未対応の環境 `{name}` です"));
+                ("", "", None)
+            }
+        };
+
+        let mut rows = String::new();
+        let mut cells = String::new();
+        loop {
+            let cell = self.row(Stop::Cell);
+            let _ = write!(cells, "<mtd>{cell}</mtd>");
+            match self.next() {
+                Some(Token::Ampersand) => {}
+                Some(Token::Command(command)) if command == "\\" => {
+                    let _ = write!(rows, "<mtr>{cells}</mtr>");
+                    cells.clear();
+                }
+                Some(Token::Command(command)) if command == "end" => {
+                    let end_name = self.raw_argument("end").trim().to_string();
+                    if end_name != name {
+                        self.errors.push(format!(
+                            "`\\begin{{{name}}}` が `\\end{{{end_name}}}` で閉じられています"
+                        ));
+                    }
+                    break;
+                }
+                Some(Token::EndGroup) => {
+                    self.errors
+                        .push(format!("`\\begin{{{name}}}` の途中で `}}` があります"));
+                }
+                _ => {
+                    self.errors.push(format!("`\\end{{{name}}}` がありません"));
+                    break;
+                }
+            }
+        }
+        // 末尾の `\\` による空行は出力しない
+        if cells != "<mtd></mtd>" {
+            let _ = write!(rows, "<mtr>{cells}</mtr>");
+        }
+
+        let table = match column_align {
+            Some(align) => format!(r#"<mtable columnalign="{align}">{rows}</mtable>"#),
+            None => format!("<mtable>{rows}</mtable>"),
+        };
+        format!(
+            "<mrow>{}{table}{}</mrow>",
+            Self::fence(open),
+            Self::fence(close)
+        )
+    }
+}
+
+/// 数式をMathMLに変換する
+///
+/// 未対応のコマンド等は `<merror>` として出力し、エラー内容も返す
+/// （呼び出し側で警告として報告する）
+pub fn to_mathml(source: &str, display: bool) -> (String, Vec<String>) {
+    let mut converter = Converter {
+        tokens: tokenize(source),
+        pos: 0,
+        display,
+        errors: Vec::new(),
+    };
+    let mut nodes = String::new();
+    while converter.peek().is_some() {
+        nodes.push_str(&converter.row(Stop::End));
+        // トップレベルの余分な `}` は読み飛ばす
+        if converter.peek().is_some() {
+            converter.next();
+            converter
+                .errors
+                .push("対応する `{` のない `}` があります".to_string());
+        }
+    }
+
+    let display_attr = if display { r#" display="block""# } else { "" };
+    let html = format!(
+        r#"<math{display_attr}><semantics><mrow>{nodes}</mrow><annotation encoding="application/x-tex">{}</annotation></semantics></math>"#,
+        escape(source.trim())
+    );
+    (html, converter.errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn body(source: &str, display: bool) -> String {
+        let (html, errors) = to_mathml(source, display);
+        assert!(errors.is_empty(), "unexpected errors: {errors:?}");
+        let start = html.find("<semantics><mrow>").unwrap() + "<semantics><mrow>".len();
+        let end = html.find("</mrow><annotation").unwrap();
+        html[start..end].to_string()
+    }
+
+    #[test]
+    fn test_fraction_and_scripts() {
+        assert_eq!(
+            body(r"\frac{a}{b} + x^2_i", false),
+            "<mfrac><mrow><mi>a</mi></mrow><mrow><mi>b</mi></mrow></mfrac><mo>+</mo><msubsup><mi>x</mi><mi>i</mi><mn>2</mn></msubsup>"
+        );
+        assert_eq!(
+            body("x^23", false),
+            "<msup><mi>x</mi><mn>2</mn></msup><mn>3</mn>"
+        );
+    }
+
+    #[test]
+    fn test_large_operator_limits() {
+        assert_eq!(
+            body(r"\sum_{i=1}^n", true),
+            "<munderover><mo>∑</mo><mrow><mi>i</mi><mo>=</mo><mn>1</mn></mrow><mi>n</mi></munderover>"
+        );
+        assert_eq!(
+            body(r"\sum_{i=1}^n", false),
+            "<msubsup><mo>∑</mo><mrow><mi>i</mi><mo>=</mo><mn>1</mn></mrow><mi>n</mi></msubsup>"
+        );
+    }
+
+    #[test]
+    fn test_environment() {
+        assert_eq!(
+            body(r"\begin{pmatrix} 1 & 0 \\ 0 & 1 \end{pmatrix}", true),
+            r#"<mrow><mo fence="true">(</mo><mtable><mtr><mtd><mn>1</mn></mtd><mtd><mn>0</mn></mtd></mtr><mtr><mtd><mn>0</mn></mtd><mtd><mn>1</mn></mtd></mtr></mtable><mo fence="true">)</mo></mrow>"#
+        );
+    }
+
+    #[test]
+    fn test_text_keeps_spaces() {
+        assert_eq!(
+            body(r"x \text{for all} y", false),
+            "<mi>x</mi><mtext>for all</mtext><mi>y</mi>"
+        );
+        assert_eq!(
+            body(r"\operatorname{arg max}_x", false),
+            r#"<msub><mi mathvariant="normal">arg max</mi><mi>x</mi></msub>"#
+        );
+        assert_eq!(
+            body(r"\mathrm{d} x", false),
+            r#"<mi mathvariant="normal">d</mi><mi>x</mi>"#
+        );
+        assert_eq!(body(r"\mathbf{v w}", false), "<mi>𝐯𝐰</mi>");
+    }
+
+    #[test]
+    fn test_escape_and_errors() {
+        let (html, errors) = to_mathml(r"a < b \unknown", false);
+        assert!(html.contains("<mo>&lt;</mo>"));
+        assert!(html.contains("<merror>"));
+        assert_eq!(errors, vec!["未対応のコマンド `\\unknown` です"]);
+    }
+}
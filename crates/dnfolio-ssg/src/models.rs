@@ -1,7 +1,12 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
-#[derive(Debug, Deserialize, Clone)]
+use chrono::{DateTime, FixedOffset};
+
+use crate::history::Revision;
+use crate::reading::ReadingStats;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Taxonomies {
     #[serde(default)]
     pub tags: Option<Vec<String>>,
@@ -9,7 +14,7 @@ pub struct Taxonomies {
     pub languages: Option<Vec<String>>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct MetaData {
     pub title: String,
     #[serde(default)]
@@ -22,8 +27,23 @@ pub struct MetaData {
     pub updated: Option<String>,
     #[serde(default)]
     pub draft: Option<bool>,
+    /// 公開日時（`YYYY-MM-DD` かRFC 3339）。この日時まではビルドに含めない
+    #[serde(default)]
+    pub publish_at: Option<String>,
     #[serde(default)]
     pub taxonomies: Option<Taxonomies>,
+    /// 所属するシリーズ名
+    #[serde(default)]
+    pub series: Option<String>,
+    /// シリーズページのURL（`/series/<series_slug>/`）。シリーズ名がASCIIでなければ指定する
+    #[serde(default)]
+    pub series_slug: Option<String>,
+    /// シリーズ内の順番（未指定の記事は公開日順で後ろに並ぶ）
+    #[serde(default)]
+    pub series_order: Option<u32>,
+    /// 翻訳版の記事同士で共通のキー（`hreflang` で相互に結ぶ）
+    #[serde(default)]
+    pub translation_key: Option<String>,
 }
 
 #[derive(Debug)]
@@ -32,16 +52,18 @@ pub struct Page {
     pub output_path: PathBuf,
     pub relative_url: PathBuf,
     pub filename: String,
+    /// 最後にコミットされた日時（サイトマップの `lastmod` に使う）
+    pub last_modified: Option<DateTime<FixedOffset>>,
 }
 
 /// 検索用のブロック要素（DOMの行番号と対応）
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContentBlock {
     pub line_num: usize,
     pub text: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Article {
     pub metadata: Option<MetaData>,
     pub content_html: String,
@@ -50,9 +72,55 @@ pub struct Article {
     pub relative_url: PathBuf,
     pub table_of_contents_html: String,
     pub source_path: PathBuf,
+    /// 見出し（アンカーIDの検証に使う）
+    pub headings: Vec<Heading>,
+    /// 本文中の内部リンク・画像の参照先
+    pub links: Vec<InternalLink>,
+    /// `<picture>` として出力した `static/` 以下の画像
+    pub images: Vec<ResponsiveImage>,
+    /// 本文の文字数・単語数（読了時間の表示に使う）
+    pub reading: ReadingStats,
+    /// ソースファイルのコミット履歴（新しい順）。Gitから毎回読むのでキャッシュしない
+    #[serde(skip)]
+    pub revisions: Vec<Revision>,
 }
 
-#[derive(Debug, Clone)]
+impl Article {
+    /// 記事タイトル（front matterがなければ「記事」）
+    pub fn title(&self) -> &str {
+        self.metadata
+            .as_ref()
+            .map(|meta| meta.title.as_str())
+            .unwrap_or("記事")
+    }
+
+    /// 記事ページのURL（`/posts/<slug>/`）
+    pub fn url(&self) -> String {
+        self.relative_url.to_string_lossy().into_owned()
+    }
+}
+
+/// 縮小版を生成してsrcset付きで出力する画像
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponsiveImage {
+    /// 元画像のURL（`/content/<slug>/<file>`）
+    pub url: String,
+    pub width: u32,
+    pub height: u32,
+    /// 透過ありならPNG、なければJPEGで縮小版を生成する
+    pub has_alpha: bool,
+    /// 縮小版の幅（元画像より小さいもののみ）
+    pub variant_widths: Vec<u32>,
+}
+
+/// 本文中のリンク・画像の参照先（Markdownソースの行番号付き）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InternalLink {
+    pub url: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Heading {
     pub level: u8,
     pub id: String,
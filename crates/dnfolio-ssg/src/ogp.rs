@@ -1,80 +1,247 @@
+//! OGP画像（1200x630のPNG）の生成
+//!
+//! ページの種類ごとに `OgpCard` でレイアウトを変え、共通の `ogp_template.svg`
+//! （背景・アイコン・サイト名）に差し込んでPNGに描画する。PNGはSVGの内容をキーにキャッシュする。
+//! 画像のパスはページのURLに対応させる（`/tags/rust/` → `/ogp/tags/rust.png`）ので、
+//! 別のページ同士で画像が上書きされることはない。
+
 use std::fs;
-use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
+use anyhow::{Context, Result};
 use base64::{Engine as _, engine::general_purpose::STANDARD};
-use slug::slugify;
+use resvg::usvg::{self, fontdb};
+
+use crate::cache::BuildCache;
+
+const OGP_TEMPLATE: &str = include_str!("./ogp_template.svg");
+pub const FONT_PATHS: [&str; 2] = [
+    "assets/NotoSansJP-Regular.ttf",
+    "assets/NotoSansJP-Bold.ttf",
+];
+const ICON_PATH: &str = "static/icons/icon-bg.png";
+
+// sakurajima.nvimの配色
+const TEXT_BRIGHT: &str = "#ebdbb2";
+const TEXT_MUTED: &str = "#8b9aaa";
+const ACCENT_CYAN: &str = "#5F9D9C";
+
+/// ページの種類ごとのOGP画像の内容
+pub enum OgpCard<'a> {
+    /// 記事・固定ページ: タイトルのみ
+    Article { title: &'a str },
+    /// トップページ: サイト名と説明
+    Home {
+        title: &'a str,
+        description: &'a str,
+    },
+    /// タグページ: タグ名と記事数
+    Tag { name: &'a str, count: usize },
+    /// タグ一覧ページ: タグの数
+    TagIndex { count: usize },
+    /// シリーズページ: シリーズ名と記事数
+    Series { name: &'a str, count: usize },
+    /// 年別・月別アーカイブ: 期間と記事数
+    Archive { title: &'a str, count: usize },
+    /// 言語別の記事一覧: 見出しと記事数
+    Language { title: &'a str, count: usize },
+    /// 404ページ
+    NotFound { message: &'a str },
+}
 
-pub fn generate_ogp_svg(page_title: &str, output_dir: &Path) -> anyhow::Result<String> {
-    let filename = format!("{}.svg", slugify(page_title));
-    let output_path = output_dir.join(&filename);
-    let static_icons_dir = PathBuf::from("static/icons");
+fn text(x: i32, y: i32, size: i32, weight: &str, fill: &str, content: &str) -> String {
+    format!(
+        "<text x=\"{x}\" y=\"{y}\" text-anchor=\"middle\" font-family=\"Noto Sans JP\" font-size=\"{size}px\" font-weight=\"{weight}\" fill=\"{fill}\">{}</text>",
+        escape_xml(content)
+    )
+}
 
-    let title_lines = split_title_into_lines(page_title, 3);
-    let line_count = title_lines.len();
+/// タイトル背景カード
+fn title_card(y: i32, height: i32) -> String {
+    format!(
+        "<rect x=\"50\" y=\"{y}\" width=\"1100\" height=\"{height}\" fill=\"url(#titleBgGradient)\" stroke=\"#3D4450\" stroke-width=\"1\" rx=\"8\" ry=\"8\" opacity=\"0.95\"/>"
+    )
+}
 
-    let title_font_size = match (line_count, page_title.len()) {
+/// 記事タイトル（最大3行で折り返し、行数と長さで文字サイズを変える）
+fn article_svg(title: &str) -> String {
+    let title_lines = split_title_into_lines(title, 3);
+    let line_count = title_lines.len() as i32;
+
+    let title_font_size = match (line_count, title.len()) {
         (3, _) => 42,
         (2, len) if len > 40 => 46,
         (2, _) => 52,
         (1, len) if len > 25 => 56,
         _ => 64,
     };
-
     let start_y = match line_count {
         3 => 250,
         2 => 280,
         _ => 315,
     };
-
     let line_height = title_font_size + 15;
 
-    // sakurajima.nvimのtext-bright色を使用
-    let text_color = "#ebdbb2";
-    let title_svg = title_lines
-        .iter()
-        .enumerate()
-        .map(|(i, line)| {
-            let y_pos = start_y + (i as i32 * line_height);
-            format!(
-                "<text x=\"600\" y=\"{}\" text-anchor=\"middle\" font-family=\"Noto Sans JP\" font-size=\"{}px\" font-weight=\"bold\" fill=\"{}\">{}</text>",
-                y_pos,
-                title_font_size,
-                text_color,
-                escape_xml(line)
-            )
-        })
-        .collect::<Vec<String>>()
-        .join("\n    ");
+    let mut parts = vec![title_card(start_y - 80, line_count * line_height + 80)];
+    parts.extend(title_lines.iter().enumerate().map(|(i, line)| {
+        text(
+            600,
+            start_y + i as i32 * line_height,
+            title_font_size,
+            "bold",
+            TEXT_BRIGHT,
+            line,
+        )
+    }));
+    parts.join("\n    ")
+}
 
-    let icon_path = static_icons_dir.join("icon-bg.png");
+/// ラベル・見出し・補足の3段（タグ・シリーズ・アーカイブなどの一覧ページ）
+fn labeled_svg(label: &str, heading: &str, footer: &str) -> String {
+    let heading_lines = split_title_into_lines(heading, 2);
+    let heading_size = if heading_lines.len() > 1 { 56 } else { 72 };
+    let line_height = heading_size + 16;
+    let heading_height = heading_lines.len() as i32 * line_height;
+    let top = 315 - (heading_height + 140) / 2;
 
-    let mut icon_file = fs::File::open(&icon_path)?;
-    let mut icon_data = Vec::new();
-    icon_file.read_to_end(&mut icon_data)?;
+    let mut parts = vec![
+        title_card(top, heading_height + 140),
+        text(600, top + 50, 28, "bold", ACCENT_CYAN, label),
+    ];
+    parts.extend(heading_lines.iter().enumerate().map(|(i, line)| {
+        text(
+            600,
+            top + 60 + (i as i32 + 1) * line_height - 16,
+            heading_size,
+            "bold",
+            TEXT_BRIGHT,
+            line,
+        )
+    }));
+    parts.push(text(
+        600,
+        top + heading_height + 115,
+        30,
+        "normal",
+        TEXT_MUTED,
+        footer,
+    ));
+    parts.join("\n    ")
+}
 
-    let base64_icon_data = STANDARD.encode(icon_data);
-    let image_data_uri = format!("data:image/png;base64,{}", base64_icon_data);
+impl OgpCard<'_> {
+    fn content_svg(&self) -> String {
+        match self {
+            OgpCard::Article { title } => article_svg(title),
+            OgpCard::Home { title, description } => [
+                title_card(170, 260),
+                text(600, 295, 96, "bold", TEXT_BRIGHT, title),
+                text(600, 375, 30, "normal", TEXT_MUTED, description),
+            ]
+            .join("\n    "),
+            OgpCard::Tag { name, count } => {
+                labeled_svg("# タグ", name, &format!("{count}件の記事"))
+            }
+            OgpCard::TagIndex { count } => {
+                labeled_svg("# タグ", "タグ一覧", &format!("{count}個のタグ"))
+            }
+            OgpCard::Series { name, count } => {
+                labeled_svg("シリーズ", name, &format!("全{count}回"))
+            }
+            OgpCard::Archive { title, count } => {
+                labeled_svg("アーカイブ", title, &format!("{count}件の記事"))
+            }
+            OgpCard::Language { title, count } => {
+                labeled_svg("Language", title, &format!("{count} articles"))
+            }
+            OgpCard::NotFound { message } => [
+                title_card(170, 260),
+                text(600, 310, 140, "bold", TEXT_BRIGHT, "404"),
+                text(600, 385, 32, "normal", TEXT_MUTED, message),
+            ]
+            .join("\n    "),
+        }
+    }
+}
 
-    const OGP_TEMPLATE: &str = include_str!("./ogp_template.svg");
+/// アイコンとページの種類ごとの内容をテンプレートに差し込んだSVG
+fn ogp_svg(card: &OgpCard, icon_data_uri: &str) -> String {
+    OGP_TEMPLATE
+        .replace("__CONTENT_SVG__", &card.content_svg())
+        .replace("__PNG_IMAGE_DATA__", icon_data_uri)
+}
 
-    let svg_content = OGP_TEMPLATE
-        .replace("__Y_POS__", &(start_y - 80).to_string())
-        .replace(
-            "__HEIGHT__",
-            &((line_count as i32 * line_height) + 80).to_string(),
-        )
-        .replace("__TITLE_SVG__", &title_svg)
-        .replace("__PNG_IMAGE_DATA__", &image_data_uri);
+/// ページのURLに対応する `ogp/` 以下の画像のパス（`/` → `index.png`、`/404.html` → `404.png`）
+fn image_path(page_url: &str) -> String {
+    let path = page_url.trim_matches('/');
+    let path = path.strip_suffix(".html").unwrap_or(path);
+    if path.is_empty() {
+        "index.png".to_string()
+    } else {
+        format!("{path}.png")
+    }
+}
+
+/// OGP画像の描画に使うフォント・アイコン・キャッシュ
+pub struct OgpRenderer<'a> {
+    options: usvg::Options<'a>,
+    icon_data_uri: String,
+    output_dir: PathBuf,
+    build_cache: &'a BuildCache,
+}
 
-    if !output_dir.exists() {
+impl<'a> OgpRenderer<'a> {
+    /// `output_dir`（`dist/ogp`）に書き出すレンダラーを作る
+    pub fn new(output_dir: &Path, build_cache: &'a BuildCache) -> Result<Self> {
+        let mut font_db = fontdb::Database::new();
+        for font_path in FONT_PATHS {
+            font_db
+                .load_font_file(font_path)
+                .with_context(|| format!("failed to load font {font_path}"))?;
+        }
+        let icon_data =
+            fs::read(ICON_PATH).with_context(|| format!("failed to read {ICON_PATH}"))?;
         fs::create_dir_all(output_dir)?;
+
+        Ok(Self {
+            options: usvg::Options {
+                fontdb: Arc::new(font_db),
+                ..Default::default()
+            },
+            icon_data_uri: format!("data:image/png;base64,{}", STANDARD.encode(icon_data)),
+            output_dir: output_dir.to_path_buf(),
+            build_cache,
+        })
     }
 
-    fs::write(&output_path, svg_content)?;
+    /// `page_url` のページのOGP画像を描画し、そのURL（`/ogp/...png`）を返す
+    pub fn render_ogp_png(&self, page_url: &str, card: &OgpCard) -> Result<String> {
+        let svg_data = ogp_svg(card, &self.icon_data_uri).into_bytes();
+        let image_path = image_path(page_url);
+        let png_path = self.output_dir.join(&image_path);
+        if let Some(parent) = png_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        self.build_cache.ogp_png(&svg_data, &png_path, |png_path| {
+            let tree = usvg::Tree::from_data(&svg_data, &self.options)?;
+            let pixmap_size = tree.size().to_int_size();
+            let mut pixmap = tiny_skia::Pixmap::new(pixmap_size.width(), pixmap_size.height())
+                .ok_or_else(|| anyhow::Error::msg("Failed to create pixmap"))?;
 
-    println!("Generated OGP image: {}", output_path.display());
-    Ok(format!("/ogp/{filename}"))
+            resvg::render(
+                &tree,
+                tiny_skia::Transform::identity(),
+                &mut pixmap.as_mut(),
+            );
+            pixmap.save_png(png_path)?;
+            println!("Generated OGP image: {}", png_path.display());
+            Ok(())
+        })?;
+
+        Ok(format!("/ogp/{image_path}"))
+    }
 }
 
 fn split_title_into_lines(title: &str, max_lines: usize) -> Vec<String> {
@@ -144,3 +311,51 @@ fn escape_xml(text: &str) -> String {
         .replace('"', "&quot;")
         .replace('\'', "&apos;")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_path_follows_page_url() {
+        assert_eq!(image_path("/"), "index.png");
+        assert_eq!(image_path("/posts/tags/"), "posts/tags.png");
+        assert_eq!(image_path("/tags/"), "tags.png");
+        assert_eq!(image_path("/tags/rust/"), "tags/rust.png");
+        assert_eq!(image_path("/en/"), "en.png");
+        assert_eq!(image_path("/en/404.html"), "en/404.png");
+    }
+
+    #[test]
+    fn test_cards_are_valid_escaped_svg() {
+        let name = "<Rust> & \"WASM\"";
+        let cards = [
+            OgpCard::Article { title: name },
+            OgpCard::Home {
+                title: name,
+                description: name,
+            },
+            OgpCard::Tag { name, count: 3 },
+            OgpCard::TagIndex { count: 10 },
+            OgpCard::Series { name, count: 2 },
+            OgpCard::Archive {
+                title: name,
+                count: 5,
+            },
+            OgpCard::Language {
+                title: name,
+                count: 1,
+            },
+            OgpCard::NotFound { message: name },
+        ];
+        for card in &cards {
+            let svg = ogp_svg(card, "data:image/png;base64,");
+            assert!(!svg.contains("__"), "placeholder left in {svg}");
+            assert!(!svg.contains("<Rust>"));
+            usvg::Tree::from_str(&svg, &usvg::Options::default()).unwrap();
+        }
+        let svg = ogp_svg(&OgpCard::Tag { name, count: 3 }, "");
+        assert!(svg.contains("&lt;Rust&gt; &amp; &quot;WASM&quot;"));
+        assert!(svg.contains("3件の記事"));
+    }
+}
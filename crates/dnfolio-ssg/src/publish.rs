@@ -0,0 +1,100 @@
+//! 下書き・予約投稿の公開判定
+//!
+//! `draft = true` の記事と `publish_at` が未来の記事は通常のビルドでは出力しない。
+//! `dnfolio serve --drafts` ではプレビューとして出力する（DRAFTバナー付き・noindex、
+//! サイトマップ・フィード・一覧ページには載せない）。
+
+use chrono::{DateTime, FixedOffset};
+
+use crate::dates;
+use crate::models::MetaData;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Visibility {
+    Published,
+    Draft,
+    /// `publish_at` が未来の記事
+    Scheduled(DateTime<FixedOffset>),
+}
+
+impl Visibility {
+    pub fn is_published(&self) -> bool {
+        *self == Visibility::Published
+    }
+
+    /// プレビュー時にバナーへ出す文言
+    pub fn banner_label(&self) -> String {
+        match self {
+            Visibility::Published => String::new(),
+            Visibility::Draft => "DRAFT".to_string(),
+            Visibility::Scheduled(publish_at) => {
+                format!("DRAFT: {} に公開予定", publish_at.format("%Y-%m-%d %H:%M"))
+            }
+        }
+    }
+}
+
+/// 記事の公開状態を `now` 時点で判定する
+///
+/// `publish_at` を解析できない場合は誤って公開しないよう下書き扱いにする
+pub fn visibility(metadata: Option<&MetaData>, now: DateTime<FixedOffset>) -> Visibility {
+    let Some(meta) = metadata else {
+        return Visibility::Published;
+    };
+    if meta.draft == Some(true) {
+        return Visibility::Draft;
+    }
+    match meta.publish_at.as_deref().map(dates::parse_datetime) {
+        None => Visibility::Published,
+        Some(None) => Visibility::Draft,
+        Some(Some(publish_at)) if publish_at > now => Visibility::Scheduled(publish_at),
+        Some(Some(_)) => Visibility::Published,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(draft: Option<bool>, publish_at: Option<&str>) -> MetaData {
+        toml::from_str::<MetaData>("title = \"t\"")
+            .map(|meta| MetaData {
+                draft,
+                publish_at: publish_at.map(String::from),
+                ..meta
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_visibility() {
+        let now = dates::parse_datetime("2026-03-01T12:00:00+09:00").unwrap();
+        assert_eq!(
+            visibility(Some(&metadata(None, None)), now),
+            Visibility::Published
+        );
+        assert_eq!(
+            visibility(Some(&metadata(Some(true), None)), now),
+            Visibility::Draft
+        );
+        assert_eq!(
+            visibility(Some(&metadata(None, Some("2026-03-01"))), now),
+            Visibility::Published
+        );
+        assert_eq!(
+            visibility(Some(&metadata(None, Some("2026-03-02"))), now),
+            Visibility::Scheduled(dates::parse_datetime("2026-03-02").unwrap())
+        );
+        assert_eq!(
+            visibility(
+                Some(&metadata(None, Some("2026-03-01T13:00:00+09:00"))),
+                now
+            ),
+            Visibility::Scheduled(dates::parse_datetime("2026-03-01T13:00:00+09:00").unwrap())
+        );
+        assert_eq!(
+            visibility(Some(&metadata(None, Some("来週"))), now),
+            Visibility::Draft
+        );
+    }
+}
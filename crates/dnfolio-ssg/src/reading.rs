@@ -0,0 +1,91 @@
+//! 文字数・単語数と読了時間
+//!
+//! 日本語（かな・漢字）は1文字ずつ、英数字は空白や記号で区切った1語ずつ数える。
+//! コードブロックは数えない（呼び出し側で除外する）。
+
+use serde::{Deserialize, Serialize};
+
+/// 1分あたりに読める日本語の文字数
+const CHARACTERS_PER_MINUTE: usize = 500;
+/// 1分あたりに読める英語の単語数
+const WORDS_PER_MINUTE: usize = 200;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReadingStats {
+    /// 日本語の文字数
+    pub characters: usize,
+    /// 英数字の単語数
+    pub words: usize,
+}
+
+fn is_japanese(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}' // ひらがな・カタカナ
+        | '\u{3400}'..='\u{4DBF}' // CJK統合漢字拡張A
+        | '\u{4E00}'..='\u{9FFF}' // CJK統合漢字
+        | '\u{F900}'..='\u{FAFF}' // CJK互換漢字
+        | '\u{FF66}'..='\u{FF9F}' // 半角カタカナ
+    )
+}
+
+impl ReadingStats {
+    /// テキストを数えて加算する
+    pub fn add(&mut self, text: &str) {
+        let mut in_word = false;
+        for c in text.chars() {
+            if is_japanese(c) {
+                self.characters += 1;
+                in_word = false;
+            } else if c.is_alphanumeric() || (in_word && matches!(c, '\'' | '-' | '_')) {
+                if !in_word {
+                    self.words += 1;
+                    in_word = true;
+                }
+            } else {
+                in_word = false;
+            }
+        }
+    }
+
+    /// 文字数と単語数の合計（JSON-LDの `wordCount`）
+    pub fn word_count(&self) -> usize {
+        self.characters + self.words
+    }
+
+    /// 読了時間（分、最低1分）
+    pub fn minutes(&self) -> usize {
+        let seconds =
+            self.characters * 60 / CHARACTERS_PER_MINUTE + self.words * 60 / WORDS_PER_MINUTE;
+        seconds.div_ceil(60).max(1)
+    }
+
+    /// ISO 8601形式の読了時間（JSON-LDの `timeRequired`）
+    pub fn time_required(&self) -> String {
+        format!("PT{}M", self.minutes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_mixed_text() {
+        let mut stats = ReadingStats::default();
+        stats.add("RustとWASMでサイトを作った。");
+        stats.add("It's a static-site generator, v2.");
+        assert_eq!(stats.characters, 9);
+        assert_eq!(stats.words, 7);
+    }
+
+    #[test]
+    fn test_minutes() {
+        let stats = ReadingStats {
+            characters: 1000,
+            words: 100,
+        };
+        assert_eq!(stats.minutes(), 3);
+        assert_eq!(stats.time_required(), "PT3M");
+        assert_eq!(ReadingStats::default().minutes(), 1);
+    }
+}
@@ -5,6 +5,7 @@ use std::path::Path;
 use anyhow::Result;
 use slug::slugify;
 
+use crate::i18n;
 use crate::models::Article;
 
 fn encode_path_segment(input: &str) -> String {
@@ -42,22 +43,26 @@ fn add_path_variants(rules: &mut BTreeSet<String>, source: &str, destination: &s
 }
 
 fn reserved_root_path(segment: &str) -> bool {
-    matches!(
-        segment,
-        "" | "about"
-            | "privacy"
-            | "posts"
-            | "tags"
-            | "content"
-            | "icons"
-            | "fonts"
-            | "ogp"
-            | "sns"
-            | "feed.xml"
-            | "sitemap.xml"
-            | "robots.txt"
-            | "404"
-    )
+    i18n::is_supported(segment)
+        || matches!(
+            segment,
+            "" | "about"
+                | "privacy"
+                | "posts"
+                | "tags"
+                | "series"
+                | "content"
+                | "icons"
+                | "fonts"
+                | "ogp"
+                | "sns"
+                | "feed.xml"
+                | "atom.xml"
+                | "feed.json"
+                | "sitemap.xml"
+                | "robots.txt"
+                | "404"
+        )
 }
 
 pub fn generate_and_write_redirects(articles: &[Article], dist_dir: &Path) -> Result<()> {
@@ -0,0 +1,185 @@
+//! 記事間のナビゲーション
+//!
+//! 時系列での前後の記事と、タグ・タイトルの語の重なりから求めた関連記事を
+//! 全記事に対して一度だけ計算する。
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+
+use maud::{Markup, html};
+
+use crate::i18n::UiStrings;
+use crate::models::Article;
+
+/// 関連記事の最大件数
+const MAX_RELATED: usize = 5;
+
+/// タグの一致をタイトルの語の一致より重く見る
+const TAG_WEIGHT: f64 = 2.0;
+
+/// 記事ページの末尾に出すリンク
+pub struct ArticleLinks<'a> {
+    /// ひとつ古い記事
+    pub previous: Option<&'a Article>,
+    /// ひとつ新しい記事
+    pub next: Option<&'a Article>,
+    pub related: Vec<&'a Article>,
+}
+
+fn tags(article: &Article) -> BTreeSet<String> {
+    article
+        .metadata
+        .as_ref()
+        .and_then(|meta| meta.taxonomies.as_ref())
+        .and_then(|taxonomies| taxonomies.tags.as_ref())
+        .map(|tags| tags.iter().map(|tag| tag.to_lowercase()).collect())
+        .unwrap_or_default()
+}
+
+/// タイトルを語に分ける
+///
+/// 英数字は連続部分を小文字の単語に、日本語は分かち書きできないので
+/// 2文字ずつのbi-gramにする
+fn title_terms(title: &str) -> BTreeSet<String> {
+    let mut terms = BTreeSet::new();
+    let mut word = String::new();
+    let mut run: Vec<char> = Vec::new();
+
+    let flush_word = |word: &mut String, terms: &mut BTreeSet<String>| {
+        if word.chars().count() >= 2 {
+            terms.insert(word.to_lowercase());
+        }
+        word.clear();
+    };
+    let flush_run = |run: &mut Vec<char>, terms: &mut BTreeSet<String>| {
+        for pair in run.windows(2) {
+            terms.insert(pair.iter().collect());
+        }
+        run.clear();
+    };
+
+    for c in title.chars() {
+        if c.is_ascii_alphanumeric() {
+            flush_run(&mut run, &mut terms);
+            word.push(c);
+        } else if c.is_alphanumeric() {
+            flush_word(&mut word, &mut terms);
+            run.push(c);
+        } else {
+            flush_word(&mut word, &mut terms);
+            flush_run(&mut run, &mut terms);
+        }
+    }
+    flush_word(&mut word, &mut terms);
+    flush_run(&mut run, &mut terms);
+    terms
+}
+
+/// 語ごとの重み（多くの記事に出てくる語ほど軽い）
+fn idf(sets: &[BTreeSet<String>]) -> HashMap<&str, f64> {
+    let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+    for set in sets {
+        for term in set {
+            *document_frequency.entry(term.as_str()).or_default() += 1;
+        }
+    }
+    let total = sets.len() as f64;
+    document_frequency
+        .into_iter()
+        .map(|(term, count)| (term, (total / count as f64).ln()))
+        .collect()
+}
+
+fn shared_weight(a: &BTreeSet<String>, b: &BTreeSet<String>, weights: &HashMap<&str, f64>) -> f64 {
+    a.intersection(b)
+        .map(|term| weights.get(term.as_str()).copied().unwrap_or(0.0))
+        .sum()
+}
+
+/// 全記事のリンクを計算する（`articles` は新しい順に並んでいること）
+pub fn compute(articles: &[Article]) -> HashMap<PathBuf, ArticleLinks<'_>> {
+    let tag_sets: Vec<BTreeSet<String>> = articles.iter().map(tags).collect();
+    let term_sets: Vec<BTreeSet<String>> = articles
+        .iter()
+        .map(|article| title_terms(article.title()))
+        .collect();
+    let tag_weights = idf(&tag_sets);
+    let term_weights = idf(&term_sets);
+
+    articles
+        .iter()
+        .enumerate()
+        .map(|(i, article)| {
+            let mut scored: Vec<(f64, usize)> = (0..articles.len())
+                .filter(|&j| j != i)
+                .map(|j| {
+                    let score = TAG_WEIGHT
+                        * shared_weight(&tag_sets[i], &tag_sets[j], &tag_weights)
+                        + shared_weight(&term_sets[i], &term_sets[j], &term_weights);
+                    (score, j)
+                })
+                .filter(|&(score, _)| score > 0.0)
+                .collect();
+            // 同点なら新しい記事を優先する
+            scored.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+
+            let links = ArticleLinks {
+                previous: articles.get(i + 1),
+                next: i.checked_sub(1).map(|j| &articles[j]),
+                related: scored
+                    .into_iter()
+                    .take(MAX_RELATED)
+                    .map(|(_, j)| &articles[j])
+                    .collect(),
+            };
+            (article.relative_url.clone(), links)
+        })
+        .collect()
+}
+
+/// 記事末尾の前後の記事・関連記事
+pub fn markup(links: &ArticleLinks, strings: &UiStrings) -> Markup {
+    html! {
+        nav class="article-nav" aria-label=(strings.article_navigation) {
+            @if let Some(previous) = links.previous {
+                a class="article-nav-prev" href=(previous.url()) rel="prev" {
+                    span class="article-nav-label" { (strings.older_article) }
+                    span class="article-nav-title" { (previous.title()) }
+                }
+            }
+            @if let Some(next) = links.next {
+                a class="article-nav-next" href=(next.url()) rel="next" {
+                    span class="article-nav-label" { (strings.newer_article) }
+                    span class="article-nav-title" { (next.title()) }
+                }
+            }
+        }
+        @if !links.related.is_empty() {
+            nav class="related-articles" aria-label=(strings.related_articles) {
+                h2 { (strings.related_articles) }
+                ul {
+                    @for article in &links.related {
+                        li {
+                            a href=(article.url()) { (article.title()) }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_title_terms() {
+        let terms = title_terms("Neovim 0.11でLSP設定");
+        assert!(terms.contains("neovim"));
+        assert!(terms.contains("11"));
+        assert!(terms.contains("lsp"));
+        assert!(terms.contains("設定"));
+        assert!(!terms.contains("0"));
+    }
+}
@@ -0,0 +1,213 @@
+//! 記事の雛形生成（`dnfolio new`）
+//!
+//! `content/YYYY-MM-DD_<タイトル>.md` を `draft = true` のfront matter付きで作成する。
+//! slugは `--slug` で指定するか、ASCIIのみのタイトルから生成する。日本語などを含むタイトルは
+//! ローマ字化すると読めないslugになる（漢字は中国語の読みになる）ので `post-YYYYMMDD` にする。
+//! 生成したslugが既存記事と重複する場合は `-2`, `-3`, ... を付ける。
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use slug::slugify;
+use walkdir::WalkDir;
+
+use crate::build;
+use crate::config::SiteConfig;
+use crate::dates;
+use crate::front_matter;
+use crate::i18n;
+use crate::models::{MetaData, Taxonomies};
+
+const CONTENT_DIR: &str = "content";
+const STATIC_CONTENT_DIR: &str = "static/content";
+
+pub struct NewPost {
+    pub title: String,
+    /// 未指定ならタイトルから生成する
+    pub slug: Option<String>,
+    pub tags: Vec<String>,
+    pub language: Option<String>,
+    /// `static/content/<slug>/` も作成する
+    pub with_assets: bool,
+}
+
+/// 既存記事のslugを集める（下書きも含む）
+fn existing_slugs() -> Result<HashSet<String>> {
+    let mut slugs = HashSet::new();
+    for entry in WalkDir::new(CONTENT_DIR).into_iter().filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if !path.is_file() || path.extension().is_none_or(|ext| ext != "md") {
+            continue;
+        }
+        let source = fs::read_to_string(path)?;
+        // 解析できない記事もファイル名由来のslugで衝突判定する
+        let metadata = front_matter::parse(&source)
+            .ok()
+            .and_then(|parsed| parsed.metadata);
+        slugs.insert(build::article_slug(path, metadata.as_ref()));
+    }
+    Ok(slugs)
+}
+
+/// ASCIIだけのテキストからslugを作る
+///
+/// ASCII以外を含む（ピンイン化されてしまう）テキストや、slugにできる文字がなければ `None`
+pub fn ascii_slug(text: &str) -> Option<String> {
+    Some(slugify(text)).filter(|slug| !slug.is_empty() && text.is_ascii())
+}
+
+/// タイトルからslugを作り、既存のものと重複しないよう連番を付ける
+///
+/// ASCII以外を含むタイトルや、slugにできる文字がないタイトルは `fallback` を使う
+fn unique_slug(title: &str, fallback: &str, existing: &HashSet<String>) -> String {
+    let base = ascii_slug(title).unwrap_or_else(|| fallback.to_string());
+    if !existing.contains(&base) {
+        return base;
+    }
+    (2..)
+        .map(|n| format!("{base}-{n}"))
+        .find(|candidate| !existing.contains(candidate))
+        .expect("unbounded range always yields a candidate")
+}
+
+/// ファイル名に使えない文字を置き換える
+fn file_name_title(title: &str) -> String {
+    title
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c => c,
+        })
+        .collect()
+}
+
+fn front_matter(metadata: &MetaData) -> Result<String> {
+    Ok(format!("+++\n{}+++\n\n", toml::to_string(metadata)?))
+}
+
+/// 記事の雛形を作成し、作成したファイルのパスを返す
+pub fn run(site: &SiteConfig, post: &NewPost) -> Result<PathBuf> {
+    let title = post.title.trim();
+    if title.is_empty() {
+        anyhow::bail!("title must not be empty");
+    }
+    let language = post.language.as_deref().unwrap_or(&site.language);
+    if !i18n::is_supported(language) {
+        anyhow::bail!("unsupported language \"{language}\"");
+    }
+
+    let date = dates::now().format("%Y-%m-%d").to_string();
+    let existing = existing_slugs()?;
+    let slug = match post.slug.as_deref().map(str::trim) {
+        Some(slug) => {
+            if slug.is_empty() || slugify(slug) != slug {
+                anyhow::bail!(
+                    "invalid slug \"{slug}\" (use lowercase letters, digits and hyphens, e.g. \"{}\")",
+                    slugify(slug)
+                );
+            }
+            if existing.contains(slug) {
+                anyhow::bail!("slug \"{slug}\" is already used by another post");
+            }
+            slug.to_string()
+        }
+        None => unique_slug(title, &format!("post-{}", date.replace('-', "")), &existing),
+    };
+
+    let path = Path::new(CONTENT_DIR).join(format!("{date}_{}.md", file_name_title(title)));
+    if path.exists() {
+        anyhow::bail!("{} already exists", path.display());
+    }
+
+    let metadata = MetaData {
+        title: title.to_string(),
+        slug: Some(slug.clone()),
+        description: Some(String::new()),
+        created: Some(date),
+        updated: None,
+        draft: Some(true),
+        publish_at: None,
+        taxonomies: Some(Taxonomies {
+            tags: Some(post.tags.clone()),
+            languages: Some(vec![language.to_string()]),
+        }),
+        series: None,
+        series_slug: None,
+        series_order: None,
+        translation_key: None,
+    };
+    fs::write(&path, front_matter(&metadata)?)
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    println!("Created: {}", path.display());
+
+    if post.with_assets {
+        let assets_dir = Path::new(STATIC_CONTENT_DIR).join(&slug);
+        fs::create_dir_all(&assets_dir)?;
+        println!("Created: {}/", assets_dir.display());
+    }
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unique_slug() {
+        let existing: HashSet<String> = ["hello-world", "hello-world-2"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(
+            unique_slug("Hello World", "post", &existing),
+            "hello-world-3"
+        );
+        assert_eq!(unique_slug("Rust WASM", "post", &existing), "rust-wasm");
+        assert_eq!(
+            unique_slug("！？", "post-20261018", &existing),
+            "post-20261018"
+        );
+        // 漢字をピンイン化したslug（`rustdessgwozuo-ru`）にはしない
+        assert_eq!(
+            unique_slug("RustでSSGを作る", "post-20261018", &existing),
+            "post-20261018"
+        );
+    }
+
+    #[test]
+    fn test_front_matter_round_trip() {
+        let metadata = MetaData {
+            title: "タイトル".to_string(),
+            slug: Some("title".to_string()),
+            description: Some(String::new()),
+            created: Some("2026-10-18".to_string()),
+            updated: None,
+            draft: Some(true),
+            publish_at: None,
+            taxonomies: Some(Taxonomies {
+                tags: Some(vec!["Rust".to_string(), "WASM".to_string()]),
+                languages: Some(vec!["ja".to_string()]),
+            }),
+            series: None,
+            series_slug: None,
+            series_order: None,
+            translation_key: None,
+        };
+        let source = front_matter(&metadata).unwrap();
+        let raw = source
+            .strip_prefix("+++\n")
+            .and_then(|rest| rest.strip_suffix("+++\n\n"))
+            .unwrap();
+        let parsed: MetaData = toml::from_str(raw).unwrap();
+        assert_eq!(parsed.slug.as_deref(), Some("title"));
+        assert_eq!(parsed.draft, Some(true));
+        assert_eq!(
+            parsed.taxonomies.and_then(|t| t.tags),
+            Some(vec!["Rust".to_string(), "WASM".to_string()])
+        );
+    }
+}
@@ -0,0 +1,302 @@
+//! 記事のシリーズ（連載）
+//!
+//! front matterの `series`（と任意の `series_slug`・`series_order`）で記事をまとめ、
+//! シリーズ一覧ページ（`/series/<slug>/`）と、記事ごとの「Part N of M」・
+//! シリーズ内の前後リンクを生成する。
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use maud::{Markup, html};
+use slug::slugify;
+
+use crate::config::SiteConfig;
+use crate::dates;
+use crate::i18n::UiStrings;
+use crate::models::Article;
+use crate::ogp::{OgpCard, OgpRenderer};
+use crate::scaffold;
+use crate::structured_data;
+use crate::templates::base::{self, PageConfig};
+
+pub struct Series<'a> {
+    pub name: String,
+    /// URLに使うslug（[`series_slug`] を参照）
+    pub slug: String,
+    /// シリーズ内の順番に並べた記事
+    pub articles: Vec<&'a Article>,
+}
+
+impl Series<'_> {
+    pub fn url(&self) -> String {
+        format!("/series/{}/", self.slug)
+    }
+}
+
+/// シリーズのslug
+///
+/// front matterの `series_slug`、ASCIIのシリーズ名ならそのslug、それ以外は
+/// シリーズ名のハッシュから作る（`slugify` は漢字をピンインにしてしまうため使わない）
+fn series_slug(name: &str, articles: &[&Article]) -> String {
+    articles
+        .iter()
+        .find_map(|article| article.metadata.as_ref()?.series_slug.as_deref())
+        .map(slugify)
+        .or_else(|| scaffold::ascii_slug(name))
+        .unwrap_or_else(|| format!("series-{}", &blake3::hash(name.as_bytes()).to_hex()[..8]))
+}
+
+/// シリーズ内での記事の位置
+pub struct Position<'a> {
+    pub series: &'a Series<'a>,
+    pub index: usize,
+}
+
+impl<'a> Position<'a> {
+    /// 1始まりの番号
+    pub fn part(&self) -> usize {
+        self.index + 1
+    }
+
+    pub fn total(&self) -> usize {
+        self.series.articles.len()
+    }
+
+    pub fn previous(&self) -> Option<&'a Article> {
+        self.index
+            .checked_sub(1)
+            .map(|index| self.series.articles[index])
+    }
+
+    pub fn next(&self) -> Option<&'a Article> {
+        self.series.articles.get(self.index + 1).copied()
+    }
+}
+
+/// 記事をシリーズごとにまとめる（シリーズ名順）
+///
+/// シリーズ内は `series_order`（未指定は後ろ）、公開日、タイトルの順に並べる
+pub fn collect(articles: &[Article]) -> Vec<Series<'_>> {
+    let mut by_name: BTreeMap<&str, Vec<&Article>> = BTreeMap::new();
+    for article in articles {
+        if let Some(name) = article.metadata.as_ref().and_then(|m| m.series.as_deref()) {
+            by_name.entry(name).or_default().push(article);
+        }
+    }
+
+    by_name
+        .into_iter()
+        .map(|(name, mut series_articles)| {
+            series_articles.sort_by_cached_key(|article| {
+                let order = article.metadata.as_ref().and_then(|m| m.series_order);
+                (
+                    order.is_none(),
+                    order,
+                    dates::resolve_article_dates(article).map(|d| d.published),
+                    article.title().to_string(),
+                )
+            });
+            Series {
+                name: name.to_string(),
+                slug: series_slug(name, &series_articles),
+                articles: series_articles,
+            }
+        })
+        .collect()
+}
+
+/// 記事が属するシリーズと位置を探す
+pub fn find<'a>(series_list: &'a [Series<'a>], article: &Article) -> Option<Position<'a>> {
+    series_list.iter().find_map(|series| {
+        series
+            .articles
+            .iter()
+            .position(|candidate| candidate.relative_url == article.relative_url)
+            .map(|index| Position { series, index })
+    })
+}
+
+/// 記事冒頭の「Part N of M」ボックス
+pub fn part_box_markup(position: &Position, strings: &UiStrings) -> Markup {
+    html! {
+        nav class="series-box" aria-label=(strings.series) {
+            p class="series-box-title" {
+                (strings.series) ": "
+                a href=(position.series.url()) { (position.series.name) }
+            }
+            p class="series-box-part" {
+                "Part " (position.part()) " of " (position.total())
+            }
+        }
+    }
+}
+
+/// 記事末尾のシリーズ内の前後リンク
+pub fn navigation_markup(position: &Position, strings: &UiStrings) -> Markup {
+    html! {
+        nav class="series-nav" aria-label=(strings.series_navigation) {
+            @if let Some(previous) = position.previous() {
+                a class="series-nav-prev" href=(previous.url()) {
+                    span class="series-nav-label" { (strings.series_previous) }
+                    span class="series-nav-title" { (previous.title()) }
+                }
+            }
+            @if let Some(next) = position.next() {
+                a class="series-nav-next" href=(next.url()) {
+                    span class="series-nav-label" { (strings.series_next) }
+                    span class="series-nav-title" { (next.title()) }
+                }
+            }
+        }
+    }
+}
+
+/// シリーズ一覧ページ（`/series/<slug>/index.html`）を生成する
+pub fn generate_series_pages(
+    site: &SiteConfig,
+    series_list: &[Series],
+    dist_dir: &Path,
+    articles_list_markup: &Markup,
+    ogp_renderer: &OgpRenderer,
+) -> Result<()> {
+    for series in series_list {
+        let series_url = series.url();
+        let page_dir = dist_dir.join(series_url.trim_matches('/'));
+        fs::create_dir_all(&page_dir)?;
+        let ogp_image_path = ogp_renderer.render_ogp_png(
+            &series_url,
+            &OgpCard::Series {
+                name: &series.name,
+                count: series.articles.len(),
+            },
+        )?;
+
+        let main_content_markup = html! {
+            h1 { "シリーズ: " (series.name) "(" (series.articles.len()) "件)" }
+            ol class="series-list" {
+                @for article in &series.articles {
+                    li {
+                        a href=(article.url()) { (article.title()) }
+                    }
+                }
+            }
+        };
+
+        let structured_data = structured_data::generate_structured_data_html(
+            site,
+            structured_data::PageType::SeriesPage {
+                series_name: &series.name,
+                url: &series_url,
+            },
+            None,
+        );
+
+        let html_output = base::layout(
+            PageConfig {
+                site,
+                page_title: &format!("シリーズ: {}", series.name),
+                canonical_url: &site.absolute_url(&series_url),
+                metadata: None,
+                ogp_image_path: Some(&ogp_image_path),
+                structured_data_html: Some(&structured_data),
+                robots_directive: Some("noindex,follow"),
+                article_dates: None,
+                extra_feed: None,
+                language: None,
+                translations: None,
+            },
+            articles_list_markup.clone(),
+            main_content_markup,
+            html! {
+                h2 { "サイト情報" }
+                ul {
+                    li {
+                        a href="/" { "ホームに戻る" }
+                    }
+                }
+            },
+        )
+        .into_string();
+        fs::write(page_dir.join("index.html"), html_output)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MetaData;
+    use std::path::PathBuf;
+
+    fn article(slug: &str, created: &str, series: Option<&str>, order: Option<u32>) -> Article {
+        Article {
+            metadata: Some(MetaData {
+                title: slug.to_string(),
+                slug: None,
+                description: None,
+                created: Some(created.to_string()),
+                updated: None,
+                draft: Some(false),
+                publish_at: None,
+                taxonomies: None,
+                series: series.map(String::from),
+                series_slug: None,
+                series_order: order,
+                translation_key: None,
+            }),
+            content_html: String::new(),
+            content_blocks: Vec::new(),
+            output_path: PathBuf::new(),
+            relative_url: PathBuf::from(format!("/posts/{slug}/")),
+            table_of_contents_html: String::new(),
+            source_path: PathBuf::new(),
+            headings: Vec::new(),
+            links: Vec::new(),
+            images: Vec::new(),
+            reading: Default::default(),
+            revisions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_collect_orders_by_series_order_then_date() {
+        let articles = vec![
+            article("c", "2024-01-01", Some("移行"), None),
+            article("b", "2024-03-01", Some("移行"), Some(2)),
+            article("a", "2024-05-01", Some("移行"), Some(1)),
+            article("d", "2023-12-01", Some("移行"), None),
+            article("other", "2024-01-01", None, None),
+        ];
+        let series_list = collect(&articles);
+        assert_eq!(series_list.len(), 1);
+        let titles: Vec<&str> = series_list[0].articles.iter().map(|a| a.title()).collect();
+        assert_eq!(titles, ["a", "b", "d", "c"]);
+        // 漢字のシリーズ名をピンイン（`yi-xing`）にはしない
+        assert!(series_list[0].url().starts_with("/series/series-"));
+
+        let position = find(&series_list, &articles[1]).unwrap();
+        assert_eq!((position.part(), position.total()), (2, 4));
+        assert_eq!(position.previous().map(Article::title), Some("a"));
+        assert_eq!(position.next().map(Article::title), Some("d"));
+        assert!(find(&series_list, &articles[4]).is_none());
+    }
+
+    #[test]
+    fn test_series_slug() {
+        let mut migration = article("a", "2024-01-01", Some("移行"), None);
+        assert_eq!(series_slug("Rust SSG", &[&migration]), "rust-ssg");
+        assert_eq!(
+            series_slug("移行", &[&migration]),
+            series_slug("移行", &[&migration])
+        );
+        assert_ne!(
+            series_slug("移行", &[&migration]),
+            series_slug("移転", &[&migration])
+        );
+
+        migration.metadata.as_mut().unwrap().series_slug = Some("migration".to_string());
+        assert_eq!(series_slug("移行", &[&migration]), "migration");
+    }
+}
@@ -1,21 +1,32 @@
+use std::convert::Infallible;
 use std::net::SocketAddr;
 
 use axum::Router;
-use axum::routing::get_service;
+use axum::extract::State;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::routing::{get, get_service};
 use socket2::{Domain, Socket, Type};
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
 use tower_http::services::ServeDir;
 
 /// 開始ポート
 const BASE_PORT: u16 = 3000;
 /// ポート探索の最大試行数
 const MAX_PORT_ATTEMPTS: u16 = 10;
+/// ライブリロード通知のSSEエンドポイント（dnfolio-wasmのlive_reloadと揃える）
+const RELOAD_ENDPOINT: &str = "/__dnfolio/reload";
 
-pub async fn run() -> anyhow::Result<()> {
+pub async fn run(reload_tx: broadcast::Sender<()>) -> anyhow::Result<()> {
     let (socket, addr) = bind_available_port()?;
     socket.listen(128)?;
 
     let serve_dir = ServeDir::new("dist");
-    let app = Router::new().fallback(get_service(serve_dir));
+    let app = Router::new()
+        .route(RELOAD_ENDPOINT, get(reload_events))
+        .fallback(get_service(serve_dir))
+        .with_state(reload_tx);
 
     println!("Listening on http://{addr}");
     println!("Press Ctrl + C for exit");
@@ -30,6 +41,16 @@ pub async fn run() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// 再ビルド完了のたびに `reload` メッセージを送るSSEストリーム
+async fn reload_events(
+    State(reload_tx): State<broadcast::Sender<()>>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    // 取りこぼし（Lagged）もリロード対象として扱う
+    let stream =
+        BroadcastStream::new(reload_tx.subscribe()).map(|_| Ok(Event::default().data("reload")));
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
 /// 使用可能なポートを探してバインドする
 fn bind_available_port() -> anyhow::Result<(Socket, SocketAddr)> {
     for port in BASE_PORT..BASE_PORT + MAX_PORT_ATTEMPTS {
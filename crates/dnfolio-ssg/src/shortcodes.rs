@@ -0,0 +1,571 @@
+//! Zola風のshortcode
+//!
+//! 本文中の `{{ name(arg="value", ...) }}` を、`SHORTCODES` に登録したRust関数で
+//! 信頼済みHTMLに展開する。引数は名前付き・位置指定のどちらでも渡せる。
+//! 引数の値はmaudでエスケープして出力するため、任意のHTMLは埋め込めない。
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use maud::html;
+
+use crate::cache::BuildCache;
+use crate::images;
+use crate::models::ResponsiveImage;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Str(String),
+    Int(i64),
+    Bool(bool),
+}
+
+impl Value {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::Str(_) => "文字列",
+            Value::Int(_) => "整数",
+            Value::Bool(_) => "真偽値",
+        }
+    }
+}
+
+/// 引数名で引けるようにした呼び出し引数
+pub struct Args {
+    values: HashMap<&'static str, Value>,
+}
+
+impl Args {
+    fn str(&self, key: &str) -> Result<Option<&str>, String> {
+        match self.values.get(key) {
+            None => Ok(None),
+            Some(Value::Str(value)) => Ok(Some(value)),
+            Some(other) => Err(format!(
+                "引数 `{key}` は文字列で指定してください（{}が渡されました）",
+                other.type_name()
+            )),
+        }
+    }
+
+    fn required_str(&self, key: &str) -> Result<&str, String> {
+        self.str(key)?
+            .ok_or_else(|| format!("引数 `{key}` は必須です"))
+    }
+
+    fn bool(&self, key: &str) -> Result<bool, String> {
+        match self.values.get(key) {
+            None => Ok(false),
+            Some(Value::Bool(value)) => Ok(*value),
+            Some(other) => Err(format!(
+                "引数 `{key}` は true/false で指定してください（{}が渡されました）",
+                other.type_name()
+            )),
+        }
+    }
+}
+
+/// 展開中に集める付随情報（リンク検証と画像の縮小版生成に使う）
+pub struct Collected<'a> {
+    pub links: Vec<String>,
+    pub images: Vec<ResponsiveImage>,
+    /// 画像の縮小版のエンコード結果（`None` なら画像を調べない）
+    build_cache: Option<&'a BuildCache>,
+}
+
+impl<'a> Collected<'a> {
+    pub fn new(build_cache: &'a BuildCache) -> Self {
+        Self {
+            links: Vec::new(),
+            images: Vec::new(),
+            build_cache: Some(build_cache),
+        }
+    }
+
+    /// 画像の寸法や縮小版を調べずにリンクだけを集める
+    pub fn links_only() -> Self {
+        Self {
+            links: Vec::new(),
+            images: Vec::new(),
+            build_cache: None,
+        }
+    }
+}
+
+type Render = fn(&Args, &mut Collected) -> Result<String, String>;
+
+struct Definition {
+    name: &'static str,
+    /// 引数名（位置指定の引数はこの順に割り当てる）
+    params: &'static [&'static str],
+    /// 段落を置き換えるブロック要素か（falseならインライン）
+    block: bool,
+    render: Render,
+}
+
+const SHORTCODES: &[Definition] = &[
+    Definition {
+        name: "youtube",
+        params: &["id", "class", "autoplay"],
+        block: true,
+        render: youtube,
+    },
+    Definition {
+        name: "figure",
+        params: &["src", "caption", "alt"],
+        block: true,
+        render: figure,
+    },
+    Definition {
+        name: "kbd",
+        params: &["keys"],
+        block: false,
+        render: kbd,
+    },
+    Definition {
+        name: "link_card",
+        params: &["url", "title", "description"],
+        block: true,
+        render: link_card,
+    },
+];
+
+fn is_safe_token(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// サイト内パスか http(s) のURLのみ許可する（`javascript:` 等を防ぐ）
+fn check_url(key: &str, url: &str) -> Result<(), String> {
+    let is_local = url.starts_with('/') && !url.starts_with("//");
+    if is_local || url.starts_with("https://") || url.starts_with("http://") {
+        Ok(())
+    } else {
+        Err(format!(
+            "引数 `{key}` にはサイト内パスか http(s) のURLを指定してください: {url}"
+        ))
+    }
+}
+
+fn youtube(args: &Args, _: &mut Collected) -> Result<String, String> {
+    let id = args.required_str("id")?;
+    if !is_safe_token(id) {
+        return Err(format!("不正な動画ID \"{id}\" です"));
+    }
+    let class = args.str("class")?.unwrap_or_default();
+    if !class.split_whitespace().all(is_safe_token) {
+        return Err(format!("不正なクラス名 \"{class}\" です"));
+    }
+    let autoplay = if args.bool("autoplay")? {
+        "?autoplay=1"
+    } else {
+        ""
+    };
+
+    Ok(html! {
+        div class=(format!("shortcode shortcode-youtube {class}").trim_end()) {
+            iframe src=(format!("https://www.youtube-nocookie.com/embed/{id}{autoplay}"))
+                title="YouTube video player" loading="lazy"
+                allow="accelerometer; autoplay; clipboard-write; encrypted-media; gyroscope; picture-in-picture"
+                referrerpolicy="strict-origin-when-cross-origin" allowfullscreen {}
+        }
+    }
+    .into_string())
+}
+
+fn figure(args: &Args, collected: &mut Collected) -> Result<String, String> {
+    let src = args.required_str("src")?;
+    check_url("src", src)?;
+    let caption = args.str("caption")?;
+    let alt = args.str("alt")?.or(caption).unwrap_or_default();
+    collected.links.push(src.to_string());
+
+    let image = collected
+        .build_cache
+        .and_then(|build_cache| images::inspect(Path::new("static"), src, build_cache));
+    let image_html = match image {
+        Some(image) => {
+            let picture = images::picture_html(&image, alt, "");
+            collected.images.push(image);
+            maud::PreEscaped(picture)
+        }
+        None => html! { img src=(src) alt=(alt) loading="lazy" decoding="async"; },
+    };
+
+    Ok(html! {
+        figure class="shortcode shortcode-figure" {
+            (image_html)
+            @if let Some(caption) = caption {
+                figcaption { (caption) }
+            }
+        }
+    }
+    .into_string())
+}
+
+fn kbd(args: &Args, _: &mut Collected) -> Result<String, String> {
+    let keys: Vec<&str> = args
+        .required_str("keys")?
+        .split('+')
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .collect();
+    if keys.is_empty() {
+        return Err("引数 `keys` が空です".to_string());
+    }
+
+    Ok(html! {
+        span class="shortcode-kbd" {
+            @for (i, key) in keys.iter().enumerate() {
+                @if i > 0 { "+" }
+                kbd { (key) }
+            }
+        }
+    }
+    .into_string())
+}
+
+fn link_card(args: &Args, collected: &mut Collected) -> Result<String, String> {
+    let url = args.required_str("url")?;
+    check_url("url", url)?;
+    let is_external = !url.starts_with('/');
+    let title = args.str("title")?.unwrap_or(url);
+    let description = args.str("description")?;
+    collected.links.push(url.to_string());
+
+    let host = url
+        .split_once("://")
+        .map(|(_, rest)| rest.split('/').next().unwrap_or(rest))
+        .unwrap_or(url);
+
+    Ok(html! {
+        a class="shortcode shortcode-link-card" href=(url)
+            target=[is_external.then_some("_blank")]
+            rel=[is_external.then_some("noopener noreferrer")] {
+            span class="link-card-title" { (title) }
+            @if let Some(description) = description {
+                span class="link-card-description" { (description) }
+            }
+            span class="link-card-host" { (host) }
+        }
+    }
+    .into_string())
+}
+
+#[derive(Debug)]
+pub struct ShortcodeError {
+    /// 入力文字列内のバイト位置
+    pub offset: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ShortcodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// 元テキストのバックスラッシュエスケープ（`\*` 等）を外す
+///
+/// shortcodeの前後のテキストは元テキストから切り出すため、Markdownの
+/// エスケープを自前で解釈する
+pub fn unescape_markdown(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\\'
+            && let Some(next) = chars.peek().copied()
+            && next.is_ascii_punctuation()
+        {
+            unescaped.push(next);
+            chars.next();
+        } else {
+            unescaped.push(c);
+        }
+    }
+    unescaped
+}
+
+/// 展開結果の断片
+#[derive(Debug, PartialEq)]
+pub enum Segment<'a> {
+    Text(&'a str),
+    Html { html: String, block: bool },
+}
+
+/// 引数リストの字句解析用カーソル
+struct Cursor<'a> {
+    source: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn rest(&self) -> &'a str {
+        &self.source[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn ident(&mut self) -> Option<&'a str> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        if len == 0 || rest.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+        self.pos += len;
+        Some(&rest[..len])
+    }
+
+    fn error(&self, message: impl Into<String>) -> ShortcodeError {
+        ShortcodeError {
+            offset: self.pos,
+            message: message.into(),
+        }
+    }
+
+    fn string(&mut self, quote: char) -> Result<String, ShortcodeError> {
+        let mut value = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some((_, escaped)) => value.push(escaped),
+                    None => break,
+                },
+                c if c == quote => {
+                    self.pos += i + c.len_utf8();
+                    return Ok(value);
+                }
+                c => value.push(c),
+            }
+        }
+        Err(self.error(
+            "文字列が閉じられていません（引数がMarkdown記法やHTMLタグで分断されていないか確認してください）",
+        ))
+    }
+
+    fn value(&mut self) -> Result<Value, ShortcodeError> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        if let Some(quote) = rest.chars().next().filter(|c| *c == '"' || *c == '\'') {
+            self.pos += 1;
+            return self.string(quote).map(Value::Str);
+        }
+
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+            .unwrap_or(rest.len());
+        let token = &rest[..len];
+        let value = match token {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            _ => Value::Int(
+                token
+                    .parse()
+                    .map_err(|_| self.error(format!("引数の値を解析できません: `{token}`")))?,
+            ),
+        };
+        self.pos += len;
+        Ok(value)
+    }
+}
+
+/// `{{` の位置から1つのshortcode呼び出しを解析する
+///
+/// `{{ name(` の形でなければ `Ok(None)`（ただのテキストとして扱う）
+fn parse_call(
+    source: &str,
+    start: usize,
+) -> Result<Option<(&'static Definition, Args, usize)>, ShortcodeError> {
+    let mut cursor = Cursor {
+        source,
+        pos: start + 2,
+    };
+    let Some(name) = cursor.ident() else {
+        return Ok(None);
+    };
+    let name_offset = cursor.pos - name.len();
+    if !cursor.eat("(") {
+        return Ok(None);
+    }
+    let Some(definition) = SHORTCODES.iter().find(|d| d.name == name) else {
+        return Err(ShortcodeError {
+            offset: name_offset,
+            message: format!("未知のshortcode `{name}` です"),
+        });
+    };
+
+    let mut values = HashMap::new();
+    let mut position = 0;
+    while !cursor.eat(")") {
+        if !values.is_empty() && !cursor.eat(",") {
+            return Err(cursor.error("引数は `,` で区切ってください"));
+        }
+        // 末尾のカンマを許可する
+        if cursor.eat(")") {
+            break;
+        }
+
+        let checkpoint = cursor.pos;
+        let key = match cursor.ident() {
+            Some(key) if cursor.eat("=") => {
+                let Some(param) = definition.params.iter().find(|p| **p == key) else {
+                    return Err(ShortcodeError {
+                        offset: checkpoint,
+                        message: format!(
+                            "`{name}` に引数 `{key}` はありません（{}）",
+                            definition.params.join(", ")
+                        ),
+                    });
+                };
+                param
+            }
+            _ => {
+                cursor.pos = checkpoint;
+                let Some(param) = definition.params.get(position) else {
+                    return Err(cursor.error(format!("`{name}` の引数が多すぎます")));
+                };
+                position += 1;
+                param
+            }
+        };
+
+        let value = cursor.value()?;
+        if values.insert(*key, value).is_some() {
+            return Err(cursor.error(format!("引数 `{key}` が重複しています")));
+        }
+    }
+
+    if !cursor.eat("}}") {
+        return Err(cursor.error("shortcodeが `}}` で閉じられていません"));
+    }
+    Ok(Some((definition, Args { values }, cursor.pos)))
+}
+
+/// テキスト中のshortcodeを展開する
+pub fn expand<'a>(
+    source: &'a str,
+    collected: &mut Collected,
+) -> Result<Vec<Segment<'a>>, ShortcodeError> {
+    let mut segments = Vec::new();
+    let mut text_start = 0;
+    let mut search_from = 0;
+
+    while let Some(found) = source[search_from..].find("{{") {
+        let start = search_from + found;
+        let Some((definition, args, end)) = parse_call(source, start)? else {
+            search_from = start + 2;
+            continue;
+        };
+
+        if text_start < start {
+            segments.push(Segment::Text(&source[text_start..start]));
+        }
+        let html = (definition.render)(&args, collected).map_err(|message| ShortcodeError {
+            offset: start,
+            message: format!("shortcode `{}`: {message}", definition.name),
+        })?;
+        segments.push(Segment::Html {
+            html,
+            block: definition.block,
+        });
+        text_start = end;
+        search_from = end;
+    }
+
+    if text_start < source.len() {
+        segments.push(Segment::Text(&source[text_start..]));
+    }
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_named_and_positional_args() {
+        let build_cache = BuildCache::disabled();
+        let mut collected = Collected::new(&build_cache);
+        let segments = expand(
+            r#"押す {{ kbd("Ctrl + C") }} と {{ kbd(keys='Esc') }}"#,
+            &mut collected,
+        )
+        .unwrap();
+        assert_eq!(
+            segments,
+            vec![
+                Segment::Text("押す "),
+                Segment::Html {
+                    html: r#"<span class="shortcode-kbd"><kbd>Ctrl</kbd>+<kbd>C</kbd></span>"#
+                        .to_string(),
+                    block: false,
+                },
+                Segment::Text(" と "),
+                Segment::Html {
+                    html: r#"<span class="shortcode-kbd"><kbd>Esc</kbd></span>"#.to_string(),
+                    block: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_expand_escapes_arguments() {
+        let build_cache = BuildCache::disabled();
+        let mut collected = Collected::new(&build_cache);
+        let segments = expand(
+            r#"{{ link_card(url="https://example.com/", title="<script>") }}"#,
+            &mut collected,
+        )
+        .unwrap();
+        let [Segment::Html { html, block: true }] = segments.as_slice() else {
+            panic!("unexpected segments: {segments:?}");
+        };
+        assert!(html.contains("&lt;script&gt;"));
+        assert_eq!(collected.links, vec!["https://example.com/"]);
+    }
+
+    #[test]
+    fn test_expand_plain_braces() {
+        let build_cache = BuildCache::disabled();
+        let mut collected = Collected::new(&build_cache);
+        let segments = expand("{{ not a shortcode }}", &mut collected).unwrap();
+        assert_eq!(segments, vec![Segment::Text("{{ not a shortcode }}")]);
+    }
+
+    #[test]
+    fn test_expand_errors() {
+        let build_cache = BuildCache::disabled();
+        let mut collected = Collected::new(&build_cache);
+        let error = expand("text {{ unknown(a=1) }}", &mut collected).unwrap_err();
+        assert_eq!(error.offset, 8);
+
+        let error = expand(r#"{{ youtube(class="yt") }}"#, &mut collected).unwrap_err();
+        assert_eq!(error.message, "shortcode `youtube`: 引数 `id` は必須です");
+
+        let error = expand(
+            r#"{{ link_card(url="javascript:alert(1)") }}"#,
+            &mut collected,
+        );
+        assert!(error.is_err());
+    }
+}
@@ -3,12 +3,9 @@
 //! Google検索エンジンがページ内容を正確に理解するための
 //! schema.org準拠の構造化データを生成する
 
+use crate::config::SiteConfig;
 use crate::models::MetaData;
-
-/// サイトの基本情報
-const SITE_NAME: &str = "dnfolio";
-const SITE_URL: &str = "https://dnfolio.me";
-const AUTHOR_NAME: &str = "Daiki";
+use crate::reading::ReadingStats;
 
 /// ページの種類に応じた構造化データ生成用の列挙型
 pub enum PageType<'a> {
@@ -20,29 +17,71 @@ pub enum PageType<'a> {
         ogp_image_url: &'a str,
         published_date: &'a str,
         modified_date: &'a str,
+        series: Option<SeriesPart<'a>>,
+        reading: &'a ReadingStats,
     },
-    /// タグ一覧ページ
+    /// タグごとの記事一覧ページ
     TagPage { tag_name: &'a str, url: &'a str },
+    /// タグ一覧ページ（`/tags/`）
+    TagIndex { url: &'a str },
+    /// シリーズ一覧ページ
+    SeriesPage { series_name: &'a str, url: &'a str },
+    /// 年別・月別アーカイブページ（`parent` は月別ページから見た年別ページの名前とURL）
+    ArchivePage {
+        title: &'a str,
+        url: &'a str,
+        parent: Option<(&'a str, &'a str)>,
+    },
+}
+
+/// 記事が属するシリーズ（BlogPostingの `isPartOf` に使う）
+pub struct SeriesPart<'a> {
+    pub name: &'a str,
+    pub url: &'a str,
+    /// シリーズ内の番号（1始まり）
+    pub position: usize,
 }
 
 /// 構造化データのHTMLを生成する
 ///
 /// # Arguments
+/// * `site` - サイト設定
 /// * `page_type` - ページの種類
 /// * `metadata` - 記事のメタデータ（記事ページの場合のみ使用）
 ///
 /// # Returns
 /// `<script type="application/ld+json">...</script>` 形式のHTML文字列
-pub fn generate_structured_data_html(page_type: PageType, metadata: Option<&MetaData>) -> String {
+pub fn generate_structured_data_html(
+    site: &SiteConfig,
+    page_type: PageType,
+    metadata: Option<&MetaData>,
+) -> String {
     let json_ld = match page_type {
-        PageType::Home => generate_website_json_ld(),
+        PageType::Home => generate_website_json_ld(site),
         PageType::Article {
             url,
             ogp_image_url,
             published_date,
             modified_date,
-        } => generate_article_json_ld(metadata, url, ogp_image_url, published_date, modified_date),
-        PageType::TagPage { tag_name, url } => generate_tag_page_json_ld(tag_name, url),
+            series,
+            reading,
+        } => generate_article_json_ld(
+            site,
+            metadata,
+            url,
+            ogp_image_url,
+            (published_date, modified_date),
+            series,
+            reading,
+        ),
+        PageType::TagPage { tag_name, url } => generate_tag_page_json_ld(site, tag_name, url),
+        PageType::TagIndex { url } => generate_tag_index_json_ld(site, url),
+        PageType::SeriesPage { series_name, url } => {
+            generate_series_page_json_ld(site, series_name, url)
+        }
+        PageType::ArchivePage { title, url, parent } => {
+            generate_archive_page_json_ld(site, title, url, parent)
+        }
     };
 
     format!(
@@ -52,36 +91,38 @@ pub fn generate_structured_data_html(page_type: PageType, metadata: Option<&Meta
 }
 
 /// WebSite構造化データ（トップページ用）
-fn generate_website_json_ld() -> String {
+fn generate_website_json_ld(site: &SiteConfig) -> String {
     serde_json::json!({
         "@context": "https://schema.org",
         "@type": "WebSite",
-        "name": SITE_NAME,
-        "url": SITE_URL,
+        "name": site.title,
+        "url": site.base_url,
         "author": {
             "@type": "Person",
-            "name": AUTHOR_NAME
+            "name": site.author.name
         },
-        "description": "Daikiの個人サイト。技術ブログや作品を公開しています。"
+        "description": site.description
     })
     .to_string()
 }
 
 /// BlogPosting構造化データ（記事ページ用）
 fn generate_article_json_ld(
+    site: &SiteConfig,
     metadata: Option<&MetaData>,
     url: &str,
     ogp_image_url: &str,
-    published_date: &str,
-    modified_date: &str,
+    (published_date, modified_date): (&str, &str),
+    series: Option<SeriesPart>,
+    reading: &ReadingStats,
 ) -> String {
     let meta = match metadata {
         Some(m) => m,
-        None => return generate_website_json_ld(), // フォールバック
+        None => return generate_website_json_ld(site), // フォールバック
     };
 
-    let full_url = format!("{}{}", SITE_URL, url);
-    let full_image_url = format!("{}{}", SITE_URL, ogp_image_url);
+    let full_url = site.absolute_url(url);
+    let full_image_url = site.absolute_url(ogp_image_url);
 
     let description = meta.description.as_deref().unwrap_or(&meta.title);
 
@@ -94,7 +135,7 @@ fn generate_article_json_ld(
                 "@type": "ListItem",
                 "position": 1,
                 "name": "ホーム",
-                "item": SITE_URL
+                "item": site.base_url
             },
             {
                 "@type": "ListItem",
@@ -106,7 +147,7 @@ fn generate_article_json_ld(
     });
 
     // メインの記事構造化データ
-    let article = serde_json::json!({
+    let mut article = serde_json::json!({
         "@context": "https://schema.org",
         "@type": "BlogPosting",
         "headline": &meta.title,
@@ -115,14 +156,16 @@ fn generate_article_json_ld(
         "url": full_url,
         "datePublished": published_date,
         "dateModified": modified_date,
+        "wordCount": reading.word_count(),
+        "timeRequired": reading.time_required(),
         "author": {
             "@type": "Person",
-            "name": AUTHOR_NAME,
-            "url": SITE_URL
+            "name": site.author.name,
+            "url": site.base_url
         },
         "publisher": {
             "@type": "Person",
-            "name": AUTHOR_NAME
+            "name": site.author.name
         },
         "mainEntityOfPage": {
             "@type": "WebPage",
@@ -130,13 +173,22 @@ fn generate_article_json_ld(
         }
     });
 
+    if let Some(series) = series {
+        article["isPartOf"] = serde_json::json!({
+            "@type": "CreativeWorkSeries",
+            "name": series.name,
+            "url": site.absolute_url(series.url)
+        });
+        article["position"] = serde_json::json!(series.position);
+    }
+
     // 複数の構造化データを配列で返す
     serde_json::json!([article, breadcrumb]).to_string()
 }
 
 /// タグページ用構造化データ
-fn generate_tag_page_json_ld(tag_name: &str, url: &str) -> String {
-    let full_url = format!("{}{}", SITE_URL, url);
+fn generate_tag_page_json_ld(site: &SiteConfig, tag_name: &str, url: &str) -> String {
+    let full_url = site.absolute_url(url);
 
     let breadcrumb = serde_json::json!({
         "@context": "https://schema.org",
@@ -146,7 +198,7 @@ fn generate_tag_page_json_ld(tag_name: &str, url: &str) -> String {
                 "@type": "ListItem",
                 "position": 1,
                 "name": "ホーム",
-                "item": SITE_URL
+                "item": site.base_url
             },
             {
                 "@type": "ListItem",
@@ -167,6 +219,116 @@ fn generate_tag_page_json_ld(tag_name: &str, url: &str) -> String {
     serde_json::json!([collection, breadcrumb]).to_string()
 }
 
+/// タグ一覧ページ用構造化データ
+fn generate_tag_index_json_ld(site: &SiteConfig, url: &str) -> String {
+    let full_url = site.absolute_url(url);
+
+    let breadcrumb = serde_json::json!({
+        "@context": "https://schema.org",
+        "@type": "BreadcrumbList",
+        "itemListElement": [
+            {
+                "@type": "ListItem",
+                "position": 1,
+                "name": "ホーム",
+                "item": site.base_url
+            },
+            {
+                "@type": "ListItem",
+                "position": 2,
+                "name": "タグ一覧",
+                "item": full_url
+            }
+        ]
+    });
+
+    let collection = serde_json::json!({
+        "@context": "https://schema.org",
+        "@type": "CollectionPage",
+        "name": "タグ一覧",
+        "url": full_url
+    });
+
+    serde_json::json!([collection, breadcrumb]).to_string()
+}
+
+/// シリーズ一覧ページ用構造化データ
+fn generate_series_page_json_ld(site: &SiteConfig, series_name: &str, url: &str) -> String {
+    let full_url = site.absolute_url(url);
+
+    let breadcrumb = serde_json::json!({
+        "@context": "https://schema.org",
+        "@type": "BreadcrumbList",
+        "itemListElement": [
+            {
+                "@type": "ListItem",
+                "position": 1,
+                "name": "ホーム",
+                "item": site.base_url
+            },
+            {
+                "@type": "ListItem",
+                "position": 2,
+                "name": series_name,
+                "item": full_url
+            }
+        ]
+    });
+
+    let series = serde_json::json!({
+        "@context": "https://schema.org",
+        "@type": "CreativeWorkSeries",
+        "name": series_name,
+        "url": full_url,
+        "author": {
+            "@type": "Person",
+            "name": site.author.name
+        }
+    });
+
+    serde_json::json!([series, breadcrumb]).to_string()
+}
+
+/// アーカイブページ用構造化データ
+fn generate_archive_page_json_ld(
+    site: &SiteConfig,
+    title: &str,
+    url: &str,
+    parent: Option<(&str, &str)>,
+) -> String {
+    let full_url = site.absolute_url(url);
+
+    let mut crumbs = vec![("ホーム".to_string(), site.base_url.clone())];
+    if let Some((parent_title, parent_url)) = parent {
+        crumbs.push((parent_title.to_string(), site.absolute_url(parent_url)));
+    }
+    crumbs.push((title.to_string(), full_url.clone()));
+
+    let breadcrumb = serde_json::json!({
+        "@context": "https://schema.org",
+        "@type": "BreadcrumbList",
+        "itemListElement": crumbs
+            .iter()
+            .enumerate()
+            .map(|(i, (name, item))| serde_json::json!({
+                "@type": "ListItem",
+                "position": i + 1,
+                "name": name,
+                "item": item
+            }))
+            .collect::<Vec<_>>()
+    });
+
+    let collection = serde_json::json!({
+        "@context": "https://schema.org",
+        "@type": "CollectionPage",
+        "name": format!("{}の記事一覧", title),
+        "url": full_url
+    });
+
+    serde_json::json!([collection, breadcrumb]).to_string()
+}
+
 /// JSON文字列をHTML <script>タグ内に安全に埋め込むためのエスケープ
 fn escape_json_for_html(json: &str) -> String {
     json.replace('<', "\\u003c")
@@ -0,0 +1,303 @@
+//! タグページ
+//!
+//! `/tags/`（タグ一覧）と `/tags/<slug>/`（タグごとの記事一覧）を生成する。
+//! タグの説明文は任意で `tags.toml` に書く:
+//!
+//! ```toml
+//! [Rust]
+//! description = "Rustで書いたツールや学んだこと"
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use maud::{Markup, html};
+use serde::Deserialize;
+use slug::slugify;
+
+use crate::config::SiteConfig;
+use crate::dates;
+use crate::feeds;
+use crate::models::{Article, TagInfo};
+use crate::ogp::{OgpCard, OgpRenderer};
+use crate::structured_data;
+use crate::templates::base::{self, PageConfig};
+
+/// デフォルトのタグ設定ファイルパス
+pub const TAGS_CONFIG_PATH: &str = "tags.toml";
+
+/// タグクラウドの文字サイズの段階数
+const CLOUD_SIZES: usize = 5;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct TagMeta {
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// `tags.toml` を読み込む（ファイルがなければ空）
+pub fn load_tag_meta(path: &Path) -> Result<HashMap<String, TagMeta>> {
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("タグ設定を読み込めません: {}", path.display()))?;
+    toml::from_str(&raw).with_context(|| format!("タグ設定の形式が不正です: {}", path.display()))
+}
+
+pub fn tag_url(tag_name: &str) -> String {
+    format!("/tags/{}/", slugify(tag_name))
+}
+
+fn published_date(article: &Article) -> Option<String> {
+    dates::resolve_article_dates(article)
+        .map(|resolved| resolved.published.format("%Y-%m-%d").to_string())
+}
+
+/// 記事数からタグクラウドの段階（1〜CLOUD_SIZES）を決める（対数スケール）
+fn cloud_size(count: usize, max_count: usize) -> usize {
+    if max_count <= 1 {
+        return 1;
+    }
+    let ratio = (count as f64).ln() / (max_count as f64).ln();
+    1 + (ratio * (CLOUD_SIZES - 1) as f64).round() as usize
+}
+
+fn sidebar_right_markup() -> Markup {
+    html! {
+        h2 { "サイト情報" }
+        ul {
+            li {
+                a href="/" { "ホームに戻る" }
+            }
+        }
+    }
+}
+
+/// タグページ共通の設定（検索結果には出さずリンクだけたどらせる）
+fn page_config<'a>(
+    site: &'a SiteConfig,
+    page_title: &'a str,
+    canonical_url: &'a str,
+    structured_data_html: &'a str,
+    ogp_image_path: &'a str,
+    extra_feed: Option<(&'a str, &'a str)>,
+) -> PageConfig<'a> {
+    PageConfig {
+        site,
+        page_title,
+        canonical_url,
+        metadata: None,
+        ogp_image_path: Some(ogp_image_path),
+        structured_data_html: Some(structured_data_html),
+        robots_directive: Some("noindex,follow"),
+        article_dates: None,
+        extra_feed,
+        language: None,
+        translations: None,
+    }
+}
+
+fn write_page(
+    dist_dir: &Path,
+    url: &str,
+    config: PageConfig,
+    articles_list_markup: &Markup,
+    main_content_markup: Markup,
+) -> Result<()> {
+    let page_dir = dist_dir.join(url.trim_matches('/'));
+    fs::create_dir_all(&page_dir)?;
+
+    let html_output = base::layout(
+        config,
+        articles_list_markup.clone(),
+        main_content_markup,
+        sidebar_right_markup(),
+    )
+    .into_string();
+    fs::write(page_dir.join("index.html"), html_output)?;
+    Ok(())
+}
+
+/// タグ一覧ページ（`/tags/index.html`）を生成する
+///
+/// 記事数の多い順に、タグクラウドと各タグの最新記事を並べる
+pub fn generate_tag_index_page(
+    site: &SiteConfig,
+    sorted_tags: &[&TagInfo],
+    tag_meta: &HashMap<String, TagMeta>,
+    dist_dir: &Path,
+    articles_list_markup: &Markup,
+    ogp_renderer: &OgpRenderer,
+) -> Result<()> {
+    let max_count = sorted_tags.iter().map(|tag| tag.count).max().unwrap_or(0);
+
+    let main_content_markup = html! {
+        h1 { "タグ一覧(" (sorted_tags.len()) "件)" }
+        div class="tag-cloud" {
+            @for tag in sorted_tags {
+                a class=(format!("tag-cloud-item tag-cloud-size-{}", cloud_size(tag.count, max_count)))
+                    href=(tag_url(&tag.name)) {
+                    (tag.name)
+                    span class="tag-index-count" { (tag.count) }
+                }
+            }
+        }
+        ul class="tag-index" {
+            @for tag in sorted_tags {
+                li {
+                    a class="tag-index-name" href=(tag_url(&tag.name)) { (tag.name) }
+                    span class="tag-index-count" { (tag.count) "件" }
+                    @if let Some(description) = tag_meta.get(&tag.name).and_then(|meta| meta.description.as_ref()) {
+                        p class="tag-description" { (description) }
+                    }
+                    @if let Some(newest) = tag.articles.first() {
+                        p class="tag-newest" {
+                            "最新: "
+                            a href=(newest.url()) { (newest.title()) }
+                            @if let Some(date) = published_date(newest) {
+                                " "
+                                time datetime=(date) { "(" (date) ")" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    let url = "/tags/";
+    let structured_data = structured_data::generate_structured_data_html(
+        site,
+        structured_data::PageType::TagIndex { url },
+        None,
+    );
+    let canonical_url = site.absolute_url(url);
+    let ogp_image_path = ogp_renderer.render_ogp_png(
+        url,
+        &OgpCard::TagIndex {
+            count: sorted_tags.len(),
+        },
+    )?;
+    write_page(
+        dist_dir,
+        url,
+        page_config(
+            site,
+            "タグ一覧",
+            &canonical_url,
+            &structured_data,
+            &ogp_image_path,
+            None,
+        ),
+        articles_list_markup,
+        main_content_markup,
+    )
+}
+
+/// タグごとの記事一覧ページ（`/tags/<slug>/index.html`）を生成する
+pub fn generate_tag_pages(
+    site: &SiteConfig,
+    tag_map: &BTreeMap<String, TagInfo>,
+    tag_meta: &HashMap<String, TagMeta>,
+    dist_dir: &Path,
+    articles_list_markup: &Markup,
+    ogp_renderer: &OgpRenderer,
+) -> Result<()> {
+    for (tag_name, tag_info) in tag_map {
+        let main_content_markup = html! {
+            h1 { "タグ: " (tag_name) "(" (tag_info.count) "件)" }
+            @if let Some(description) = tag_meta.get(tag_name).and_then(|meta| meta.description.as_ref()) {
+                p class="tag-description" { (description) }
+            }
+            ul class="tag-article-list" {
+                @for article in &tag_info.articles {
+                    li {
+                        @if let Some(date) = published_date(article) {
+                            time datetime=(date) { (date) }
+                        }
+                        a href=(article.url()) { (article.title()) }
+                        @if let Some(meta) = &article.metadata && let Some(ref description) = meta.description {
+                            p class="tag-article-description" { (description) }
+                        }
+                        @if let Some(meta) = &article.metadata
+                            && let Some(ref taxonomies) = meta.taxonomies
+                            && let Some(ref tags) = taxonomies.tags {
+                            ul class="badge-list" {
+                                @for tag in tags {
+                                    li {
+                                        a class="badge badge-tag" href=(tag_url(tag)) { (tag) }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        };
+
+        let url = tag_url(tag_name);
+        let structured_data = structured_data::generate_structured_data_html(
+            site,
+            structured_data::PageType::TagPage {
+                tag_name,
+                url: &url,
+            },
+            None,
+        );
+        let page_title = format!("タグ: {tag_name}");
+        let canonical_url = site.absolute_url(&url);
+        let feed_title = format!("{} - タグ: {}", site.title, tag_name);
+        let feed_url = feeds::tag_feed_url(tag_name);
+        let ogp_image_path = ogp_renderer.render_ogp_png(
+            &url,
+            &OgpCard::Tag {
+                name: tag_name,
+                count: tag_info.count,
+            },
+        )?;
+        write_page(
+            dist_dir,
+            &url,
+            page_config(
+                site,
+                &page_title,
+                &canonical_url,
+                &structured_data,
+                &ogp_image_path,
+                Some((&feed_title, &feed_url)),
+            ),
+            articles_list_markup,
+            main_content_markup,
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cloud_size() {
+        assert_eq!(cloud_size(1, 1), 1);
+        assert_eq!(cloud_size(1, 11), 1);
+        assert_eq!(cloud_size(11, 11), CLOUD_SIZES);
+        assert_eq!(cloud_size(3, 11), 3);
+    }
+
+    #[test]
+    fn test_load_tag_meta() {
+        let meta: HashMap<String, TagMeta> =
+            toml::from_str("[Rust]\ndescription = \"Rustの記事\"\n\n[\"Linux Mint\"]\n").unwrap();
+        assert_eq!(meta["Rust"].description.as_deref(), Some("Rustの記事"));
+        assert!(meta["Linux Mint"].description.is_none());
+        assert!(
+            load_tag_meta(Path::new("no-such-tags.toml"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -1,3 +1,6 @@
+use crate::config::SiteConfig;
+use crate::feeds;
+use crate::i18n;
 use crate::models::MetaData;
 use crate::templates::base_stylesheet::BASE_STYLESHEET;
 use crate::templates::icons;
@@ -27,6 +30,7 @@ const RUST_GEAR_SVG: &str = r##"<svg viewBox="0 0 100 100" xmlns="http://www.w3.
 </svg>"##;
 
 pub struct PageConfig<'a> {
+    pub site: &'a SiteConfig,
     pub page_title: &'a str,
     pub canonical_url: &'a str,
     pub metadata: Option<&'a MetaData>,
@@ -34,6 +38,12 @@ pub struct PageConfig<'a> {
     pub structured_data_html: Option<&'a str>,
     pub robots_directive: Option<&'a str>,
     pub article_dates: Option<(&'a str, &'a str)>,
+    /// サイト全体のフィードに加えて案内するフィード（タイトル, URL）
+    pub extra_feed: Option<(&'a str, &'a str)>,
+    /// ページの言語（未指定ならサイトの言語）
+    pub language: Option<&'a str>,
+    /// 翻訳版の（言語コード, 絶対URL）。`hreflang` に使う
+    pub translations: Option<&'a [(String, String)]>,
 }
 
 // 記事ページで使用する拡張設定
@@ -69,12 +79,16 @@ pub fn layout_with_toc(
     sidebar_left_markup: Markup,
     main_content_markup: Markup,
 ) -> Markup {
+    let site = config.base.site;
+    let language = config.base.language.unwrap_or(&site.language);
+    let strings = i18n::ui(language);
+
     let description = config
         .base
         .metadata
         .and_then(|m| m.description.as_ref())
         .map(|d| d.as_str())
-        .unwrap_or("プログラムを良く書く人の個人サイトです。");
+        .unwrap_or(&config.base.site.description);
 
     let keywords = config
         .base
@@ -82,7 +96,7 @@ pub fn layout_with_toc(
         .and_then(|m| m.taxonomies.as_ref())
         .and_then(|t| t.tags.as_ref())
         .map(|tags| tags.join(", "))
-        .unwrap_or_else(|| "ポエム".to_string());
+        .unwrap_or_else(|| strings.default_keywords.to_string());
 
     // ファイルタイプの判定（記事ページかホームか）
     let file_type = if config.base.canonical_url.contains("/posts/") {
@@ -100,7 +114,7 @@ pub fn layout_with_toc(
             "{}.md",
             config.base.page_title.chars().take(30).collect::<String>()
         )
-    } else if config.base.canonical_url == "https://dnfolio.me/" {
+    } else if config.base.canonical_url == config.base.site.home_url() {
         "index.md".to_string()
     } else {
         format!("{}.md", config.base.page_title)
@@ -108,7 +122,7 @@ pub fn layout_with_toc(
 
     html! {
         (DOCTYPE)
-        html lang="ja" {
+        html lang=(language) {
             head {
                 meta charset="utf-8";
                 meta name="viewport" content="width=device-width, initial-scale=1";
@@ -119,16 +133,24 @@ pub fn layout_with_toc(
                 // 注意: frame-ancestorsはmeta要素では無効（HTTPヘッダーでのみ有効）
                 // 注意: 'unsafe-inline'はGoogle Analytics等のインラインスクリプトに必要
                 //       SSGではnonce/hashを動的生成できないため必要
-                meta http-equiv="Content-Security-Policy" content="default-src 'self'; script-src 'self' 'unsafe-inline' 'wasm-unsafe-eval' https://www.googletagmanager.com https://www.google-analytics.com https://static.cloudflareinsights.com https://bst.heion.net https://blueskytimeline.com; style-src 'self' 'unsafe-inline'; font-src 'self'; img-src 'self' data: https:; connect-src 'self' https://www.google-analytics.com https://region1.google-analytics.com https://cloudflareinsights.com https://bst.heion.net https://blueskytimeline.com; base-uri 'self'; form-action 'self';";
+                meta http-equiv="Content-Security-Policy" content="default-src 'self'; script-src 'self' 'unsafe-inline' 'wasm-unsafe-eval' https://www.googletagmanager.com https://www.google-analytics.com https://static.cloudflareinsights.com https://bst.heion.net https://blueskytimeline.com; style-src 'self' 'unsafe-inline'; font-src 'self'; frame-src https://www.youtube-nocookie.com; img-src 'self' data: https:; connect-src 'self' https://www.google-analytics.com https://region1.google-analytics.com https://cloudflareinsights.com https://bst.heion.net https://blueskytimeline.com; base-uri 'self'; form-action 'self';";
 
                 // Referrer Policy - 外部サイトにはオリジンのみ送信
                 meta name="referrer" content="strict-origin-when-cross-origin";
 
                 title { (config.base.page_title) }
                 link rel="canonical" href=(config.base.canonical_url);
+                @if let Some(translations) = config.base.translations {
+                    @for (translation_language, translation_url) in translations {
+                        link rel="alternate" hreflang=(translation_language) href=(translation_url);
+                    }
+                    @if let Some((_, default_url)) = translations.iter().find(|(code, _)| *code == site.language) {
+                        link rel="alternate" hreflang="x-default" href=(default_url);
+                    }
+                }
                 meta name="description" content=(description);
                 meta name="keywords" content=(keywords);
-                meta name="author" content="Daiki Nakashima";
+                meta name="author" content=(site.author_full_name());
 
 
                 @if let Some(robots_directive) = config.base.robots_directive {
@@ -138,19 +160,19 @@ pub fn layout_with_toc(
                 meta property="og:title" content=(config.base.page_title);
                 meta property="og:description" content=(description);
                 meta property="og:type" content=(if config.base.article_dates.is_some() { "article" } else { "website" });
-                meta property="og:site_name" content="dnfolio";
+                meta property="og:site_name" content=(site.title);
                 meta property="og:url" content=(config.base.canonical_url);
                 @if let Some((published_time, modified_time)) = config.base.article_dates {
                     meta property="article:published_time" content=(published_time);
                     meta property="article:modified_time" content=(modified_time);
                 }
                 @if let Some(image_path) = config.base.ogp_image_path {
-                    meta property="og:image" content=(format!("https://dnfolio.me{image_path}"));
+                    meta property="og:image" content=(site.absolute_url(image_path));
                     meta property="og:image:width" content="1200";
                     meta property="og:image:height" content="630";
                     meta property="og:image:type" content="image/png";
                 } @else {
-                    meta property="og:image" content=(format!("https://dnfolio.me/icons/icon.png"));
+                    meta property="og:image" content=(site.absolute_url("/icons/icon.png"));
                     meta property="og:image:width" content="1200";
                     meta property="og:image:height" content="630";
                     meta property="og:image:type" content="image/png";
@@ -159,16 +181,23 @@ pub fn layout_with_toc(
                 meta name="twitter:card" content="summary_large_image";
                 meta name="twitter:title" content=(config.base.page_title);
                 meta name="twitter:description" content=(description);
-                meta name="twitter:site" content="@dnfolio_me";
+                @if let Some(twitter) = &site.author.twitter {
+                    meta name="twitter:site" content=(twitter);
+                }
                 @if let Some(image_path) = config.base.ogp_image_path {
-                    meta name="twitter:image" content=(format!("https://dnfolio.me{image_path}"));
+                    meta name="twitter:image" content=(site.absolute_url(image_path));
                 } @else {
-                    meta name="twitter:image" content=(format!("https://dnfolio.me/icons/icon.png"));
+                    meta name="twitter:image" content=(site.absolute_url("/icons/icon.png"));
                 }
 
                 link rel="shortcut icon" href="/icons/favicon.ico" type="image/x-icon";
                 link rel="apple-touch-icon" href="/icons/icon.png";
-                link rel="alternate" type="application/rss+xml" title="dnfolio" href="/feed.xml";
+                link rel="alternate" type="application/rss+xml" title=(site.title) href=(feeds::RSS_PATH);
+                link rel="alternate" type="application/atom+xml" title=(site.title) href=(feeds::ATOM_PATH);
+                link rel="alternate" type="application/feed+json" title=(site.title) href=(feeds::JSON_FEED_PATH);
+                @if let Some((feed_title, feed_url)) = config.base.extra_feed {
+                    link rel="alternate" type="application/rss+xml" title=(feed_title) href=(feed_url);
+                }
 
                 @if let Some(json_ld) = config.base.structured_data_html {
                     (PreEscaped(json_ld))
@@ -188,13 +217,13 @@ pub fn layout_with_toc(
                 // タイトルバー（Neovim風）
                 header class="titlebar" {
                     div class="titlebar-left" {
-                        button id="hamburger-btn" class="hamburger-btn" aria-label="Menu" {
+                        button id="hamburger-btn" class="hamburger-btn" aria-label=(strings.menu) {
                             span {}
                             span {}
                             span {}
                         }
                         h1 {
-                            a href="/" { "dnfolio" }
+                            a href="/" { (site.title) }
                         }
                     }
                     div class="window-buttons" {
@@ -211,7 +240,7 @@ pub fn layout_with_toc(
                     aside id="sidebar-left" class="sidebar-left" {
                         div class="sidebar-header" {
                             h2 { "EXPLORER" }
-                            button id="sidebar-close-btn" class="sidebar-close-btn" aria-label="Close sidebar" {
+                            button id="sidebar-close-btn" class="sidebar-close-btn" aria-label=(strings.close_sidebar) {
                                 (PreEscaped(icons::arrow_right(16)))
                             }
                         }
@@ -252,8 +281,8 @@ pub fn layout_with_toc(
 
                 // モバイル用ハイライトナビゲーション
                 div id="highlight-nav" class="highlight-nav" {
-                    button id="highlight-nav-prev" class="highlight-nav-btn" title="前へ (N)" { "↑" }
-                    button id="highlight-nav-next" class="highlight-nav-btn" title="次へ (n)" { "↓" }
+                    button id="highlight-nav-prev" class="highlight-nav-btn" title=(strings.previous_match) { "↑" }
+                    button id="highlight-nav-next" class="highlight-nav-btn" title=(strings.next_match) { "↓" }
                 }
 
                 // 固定フッター（ステータスライン + コマンドライン）
@@ -300,37 +329,37 @@ pub fn layout_with_toc(
                         button class="bottomsheet-preset-btn" data-command=":search" {
                             span class="preset-icon" { (PreEscaped(icons::search(16))) }
                             span class="preset-cmd" { ":search" }
-                            span class="preset-desc" { "記事をGrep検索" }
+                            span class="preset-desc" { (strings.search_articles) }
                         }
                         button class="bottomsheet-preset-btn" data-command=":tags" {
                             span class="preset-icon" { (PreEscaped(icons::tag(16))) }
                             span class="preset-cmd" { ":tags" }
-                            span class="preset-desc" { "タグで絞り込み" }
+                            span class="preset-desc" { (strings.filter_by_tag) }
                         }
                         button class="bottomsheet-preset-btn" data-command="/search" {
                             span class="preset-icon" { (PreEscaped(icons::text_search(16))) }
                             span class="preset-cmd" { "/pattern" }
-                            span class="preset-desc" { "ページ内検索" }
+                            span class="preset-desc" { (strings.search_in_page) }
                         }
                         button class="bottomsheet-preset-btn" data-command=":help" {
                             span class="preset-icon" { (PreEscaped(icons::help_circle(16))) }
                             span class="preset-cmd" { ":help" }
-                            span class="preset-desc" { "キーバインド一覧" }
+                            span class="preset-desc" { (strings.key_bindings) }
                         }
                         button class="bottomsheet-preset-btn" data-command=":version" {
                             span class="preset-icon" { (PreEscaped(icons::info(16))) }
                             span class="preset-cmd" { ":version" }
-                            span class="preset-desc" { "バージョン情報" }
+                            span class="preset-desc" { (strings.version_info) }
                         }
                         button class="bottomsheet-preset-btn" data-command=":noh" {
                             span class="preset-icon" { (PreEscaped(icons::x_circle(16))) }
                             span class="preset-cmd" { ":noh" }
-                            span class="preset-desc" { "検索ハイライトを消す" }
+                            span class="preset-desc" { (strings.clear_highlight) }
                         }
                         button class="bottomsheet-preset-btn" data-command=":smile" {
                             span class="preset-icon" { (PreEscaped(icons::smile(16))) }
                             span class="preset-cmd" { ":smile" }
-                            span class="preset-desc" { "イースターエッグ" }
+                            span class="preset-desc" { (strings.easter_egg) }
                         }
                     }
                 }
@@ -364,10 +393,10 @@ pub fn layout_with_toc(
                                 span id="grep-results-count" { "0 results" }
                             }
                             span {
-                                kbd { "j/k" } "移動  "
-                                kbd { "Enter" } "選択  "
+                                kbd { "j/k" } (strings.key_move) "  "
+                                kbd { "Enter" } (strings.key_select) "  "
                                 kbd { "i/a" } "INSERT  "
-                                kbd { "Esc" } "NORMAL→閉じる"
+                                kbd { "Esc" } (strings.key_close)
                             }
                         }
                     }
@@ -400,9 +429,9 @@ pub fn layout_with_toc(
                                 span id="tags-count" { "0 tags" }
                             }
                             span {
-                                kbd { "j/k" } "移動  "
-                                kbd { "Enter" } "選択  "
-                                kbd { "Esc" } "モード切替/閉じる"
+                                kbd { "j/k" } (strings.key_move) "  "
+                                kbd { "Enter" } (strings.key_select) "  "
+                                kbd { "Esc" } (strings.key_toggle_mode)
                             }
                         }
                     }
@@ -344,6 +344,16 @@ body {
     color: var(--accent-yellow);
 }
 
+/* 年/月のアーカイブページへのリンク */
+.folder-link {
+    color: inherit;
+    text-decoration: none;
+}
+
+.folder-link:hover {
+    text-decoration: underline;
+}
+
 /* 目次アイテム（旧スタイル - ファイルツリー内用） */
 .toc-expanded .toc-item {
     color: var(--text-muted);
@@ -517,7 +527,8 @@ body {
 .main-content > pre,
 .main-content > table,
 .main-content > hr,
-.main-content > .code-block-wrapper {
+.main-content > .code-block-wrapper,
+.main-content > .shortcode {
     position: relative;
     counter-increment: line-number;
 }
@@ -533,7 +544,8 @@ body {
 .main-content > pre::before,
 .main-content > table::before,
 .main-content > hr::before,
-.main-content > .code-block-wrapper::before {
+.main-content > .code-block-wrapper::before,
+.main-content > .shortcode::before {
     content: counter(line-number);
     position: absolute;
     left: -3.5em;
@@ -572,7 +584,8 @@ body {
 .main-content > pre:hover::before,
 .main-content > table:hover::before,
 .main-content > hr:hover::before,
-.main-content > .code-block-wrapper:hover::before {
+.main-content > .code-block-wrapper:hover::before,
+.main-content > .shortcode:hover::before {
     color: var(--accent-cyan);
 }
 
@@ -594,7 +607,8 @@ body {
 .main-content > blockquote.current-line::before,
 .main-content > pre.current-line::before,
 .main-content > table.current-line::before,
-.main-content > .code-block-wrapper.current-line::before {
+.main-content > .code-block-wrapper.current-line::before,
+.main-content > .shortcode.current-line::before {
     color: #2BB6BA !important;
     font-weight: 700 !important;
     text-shadow: 0 0 8px rgba(43, 182, 186, 0.5) !important;
@@ -709,6 +723,21 @@ a:hover {
     text-transform: lowercase;
 }
 
+/* ファイル名（```rust,title=src/main.rs） */
+.code-title {
+    font-family: var(--font-mono);
+    font-size: 0.8rem;
+    color: var(--text-bright);
+    overflow: hidden;
+    text-overflow: ellipsis;
+    white-space: nowrap;
+}
+
+.code-title + .code-lang {
+    margin-left: 0.75em;
+    margin-right: auto;
+}
+
 .code-copy-btn {
     display: flex;
     align-items: center;
@@ -738,8 +767,8 @@ a:hover {
     height: 14px;
 }
 
+/* 背景色・文字色はシンタックスハイライトのテーマ（.hl-code）で指定 */
 .code-block-wrapper pre {
-    background-color: #1a1f26;
     border: none;
     border-left: 3px solid var(--accent-cyan);
     font-family: var(--font-code);
@@ -750,6 +779,52 @@ a:hover {
     font-size: 0.9rem;
 }
 
+/* 行番号・強調行（```rust,linenos,hl_lines=3-5） */
+.code-line {
+    display: inline-block;
+    min-width: 100%;
+    min-height: 1lh;
+}
+
+.code-line-highlighted {
+    background-color: rgba(43, 182, 186, 0.12);
+    box-shadow: inset 3px 0 0 var(--accent-cyan-bright);
+}
+
+.code-line-number {
+    display: inline-block;
+    width: 3ch;
+    margin-right: 1em;
+    color: var(--text-muted);
+    text-align: right;
+    user-select: none;
+}
+
+/* 差分表示（```diff-rust） */
+.code-diff-marker {
+    display: inline-block;
+    width: 1.5ch;
+    margin-right: 0.5em;
+    color: var(--text-muted);
+    user-select: none;
+}
+
+.code-line-added {
+    background-color: rgba(61, 161, 99, 0.15);
+}
+
+.code-line-added .code-diff-marker {
+    color: var(--accent-green-bright);
+}
+
+.code-line-removed {
+    background-color: rgba(227, 76, 54, 0.15);
+}
+
+.code-line-removed .code-diff-marker {
+    color: var(--accent-red-bright);
+}
+
 /* 通常のpre（ラッパーなし） */
 pre:not(.code-block-wrapper pre) {
     background-color: #1a1f26;
@@ -794,6 +869,101 @@ blockquote {
 
 blockquote p:last-child { margin-bottom: 0; }
 
+/* GitHub形式のアラート（> [!NOTE] 等） */
+blockquote.markdown-alert-note { --alert-color: var(--accent-blue-light); }
+blockquote.markdown-alert-tip { --alert-color: var(--accent-green-bright); }
+blockquote.markdown-alert-important { --alert-color: var(--accent-cyan-bright); }
+blockquote.markdown-alert-warning { --alert-color: var(--accent-orange-bright); }
+blockquote.markdown-alert-caution { --alert-color: var(--accent-red-bright); }
+
+blockquote[class^="markdown-alert-"] {
+    border-left-color: var(--alert-color);
+    color: var(--text-primary);
+}
+
+.markdown-alert-title {
+    display: flex;
+    align-items: center;
+    gap: 0.5em;
+    margin-bottom: 0.5em;
+    color: var(--alert-color);
+    font-family: var(--font-mono);
+    font-weight: 700;
+}
+
+.markdown-alert-title svg {
+    flex-shrink: 0;
+}
+
+math[display="block"] {
+    overflow-x: auto;
+    overflow-y: hidden;
+    padding: 0.25em 0;
+}
+
+/* shortcode */
+.shortcode {
+    margin: 1.5em 0;
+}
+
+.shortcode-youtube iframe {
+    display: block;
+    width: min(560px, 100%);
+    aspect-ratio: 16 / 9;
+    border: 1px solid var(--border-color);
+    border-radius: 4px;
+}
+
+.shortcode-figure figcaption {
+    margin-top: 0.5em;
+    color: var(--text-muted);
+    font-size: 0.85rem;
+}
+
+.shortcode-kbd kbd {
+    font-family: var(--font-mono);
+    font-size: 0.85em;
+    background: var(--bg-secondary);
+    color: var(--text-bright);
+    border: 1px solid var(--border-color);
+    border-bottom-width: 2px;
+    border-radius: 3px;
+    padding: 0.1em 0.4em;
+    margin: 0 0.1em;
+}
+
+a.shortcode-link-card {
+    display: flex;
+    flex-direction: column;
+    gap: 0.25em;
+    max-width: 560px;
+    padding: 0.75em 1em;
+    background: var(--bg-secondary);
+    border: 1px solid var(--border-color);
+    border-left: 3px solid var(--accent-cyan);
+    border-radius: 4px;
+}
+
+a.shortcode-link-card:hover {
+    border-left-color: var(--accent-cyan-bright);
+    text-decoration: none;
+}
+
+.link-card-title {
+    color: var(--accent-blue-light);
+    font-weight: 700;
+}
+
+.link-card-description {
+    color: var(--text-primary);
+    font-size: 0.9rem;
+}
+
+.link-card-host {
+    color: var(--text-muted);
+    font-size: 0.8rem;
+}
+
 /* テーブル */
 .main-content table {
     border-collapse: collapse;
@@ -2282,6 +2452,205 @@ blockquote p:last-child { margin-bottom: 0; }
     border: 1px solid var(--accent-cyan);
 }
 
+.badge-reading {
+    background: var(--bg-secondary);
+    color: var(--accent-yellow);
+    border: 1px solid var(--border-color);
+}
+
+/* ========================================
+   シリーズ
+   ======================================== */
+.series-box {
+    margin: 0.75em 0;
+    padding: 8px 12px;
+    border-left: 3px solid var(--accent-cyan);
+    background: var(--bg-secondary);
+    font-family: var(--font-mono);
+    font-size: 0.85rem;
+}
+
+.series-box p {
+    margin: 0;
+}
+
+.series-box-part {
+    color: var(--text-muted);
+}
+
+/* ========================================
+   シリーズ内・前後の記事ナビゲーション
+   ======================================== */
+.series-nav,
+.article-nav {
+    display: flex;
+    justify-content: space-between;
+    gap: 12px;
+    margin: 2em 0 1em;
+}
+
+.series-nav a,
+.article-nav a {
+    display: flex;
+    flex-direction: column;
+    max-width: 48%;
+    padding: 8px 12px;
+    border: 1px solid var(--border-color);
+    border-radius: 4px;
+    text-decoration: none;
+}
+
+.series-nav-next,
+.article-nav-next {
+    margin-left: auto;
+    text-align: right;
+}
+
+.series-nav-label,
+.article-nav-label {
+    color: var(--text-muted);
+    font-family: var(--font-mono);
+    font-size: 0.75rem;
+}
+
+/* 関連記事 */
+.related-articles {
+    margin: 1em 0;
+    padding-top: 0.5em;
+    border-top: 1px solid var(--border-color);
+}
+
+.related-articles h2 {
+    margin: 0.5em 0;
+    font-size: 1rem;
+}
+
+.related-articles ul {
+    margin: 0;
+    padding-left: 1.5em;
+}
+
+/* 更新履歴（Gitのコミット） */
+.revision-history {
+    margin: 1em 0;
+    font-size: 0.85rem;
+    color: var(--text-muted);
+}
+
+.revision-history summary {
+    cursor: pointer;
+    color: var(--text-primary);
+}
+
+.revision-history ol {
+    margin: 0.5em 0 0;
+    padding-left: 1.5em;
+    list-style: none;
+}
+
+.revision-history li::before {
+    content: none;
+}
+
+.revision-history time {
+    font-family: var(--font-mono);
+}
+
+/* ========================================
+   アーカイブページ
+   ======================================== */
+.archive-description {
+    margin: 0.25em 0 0.5em;
+    color: var(--text-muted);
+    font-size: 0.85rem;
+}
+
+/* 下書きプレビュー（serve --drafts） */
+.draft-banner {
+    margin: 0 0 1.5em;
+    padding: 8px 12px;
+    border: 1px solid var(--status-warn);
+    border-left-width: 4px;
+    color: var(--status-warn);
+    font-weight: bold;
+    letter-spacing: 0.05em;
+}
+
+/* ========================================
+   タグ一覧・タグページ
+   ======================================== */
+.tag-cloud {
+    display: flex;
+    flex-wrap: wrap;
+    align-items: baseline;
+    gap: 6px 12px;
+    margin: 1em 0;
+}
+
+.tag-cloud-item {
+    font-family: var(--font-mono);
+    color: var(--accent-cyan-light);
+    text-decoration: none;
+}
+
+.tag-cloud-size-1 { font-size: 0.8rem; }
+.tag-cloud-size-2 { font-size: 0.95rem; }
+.tag-cloud-size-3 { font-size: 1.1rem; }
+.tag-cloud-size-4 { font-size: 1.3rem; }
+.tag-cloud-size-5 { font-size: 1.5rem; }
+
+/* 検索パネルの .tag-count とは別（一覧・サイドバーのタグ件数） */
+.tag-index-count {
+    margin-left: 4px;
+    color: var(--text-muted);
+    font-family: var(--font-mono);
+    font-size: 0.75rem;
+}
+
+.tag-description,
+.tag-newest,
+.tag-article-description {
+    margin: 0.25em 0 0.5em;
+    color: var(--text-muted);
+    font-size: 0.85rem;
+}
+
+.tag-article-list time {
+    margin-right: 8px;
+    color: var(--text-muted);
+    font-family: var(--font-mono);
+    font-size: 0.8rem;
+}
+
+a.badge-tag {
+    text-decoration: none;
+}
+
+/* ========================================
+   404ページ
+   ======================================== */
+.not-found {
+    text-align: center;
+    padding: 4rem 1rem;
+}
+
+.not-found-code {
+    font-size: 4rem;
+    margin-bottom: 1rem;
+    color: var(--text-muted);
+}
+
+.not-found-message {
+    font-size: 1.25rem;
+    color: var(--text-muted);
+    margin-bottom: 2rem;
+}
+
+.not-found-home {
+    color: var(--accent-cyan-light);
+    text-decoration: none;
+}
+
 /* 検索ハイライト（ページ遷移後） */
 .search-highlight {
     background: var(--accent-yellow);
@@ -0,0 +1,73 @@
+//! `dnfolio serve` 用のファイル監視
+//!
+//! content/・pages/・static/ の変更を検知して再ビルドし、
+//! 完了したら開いているブラウザへリロードを通知する。
+
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use anyhow::Result;
+use notify::event::{AccessKind, AccessMode, ModifyKind};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::broadcast;
+
+use crate::build::{self, BuildOptions};
+use crate::config::SiteConfig;
+
+/// 監視対象ディレクトリ
+const WATCH_DIRS: [&str; 3] = ["content", "pages", "static"];
+/// エディタの連続保存をまとめて1回の再ビルドにする待ち時間
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// 監視を開始する。返り値のwatcherをdropすると監視が止まる
+pub fn spawn(
+    site: SiteConfig,
+    options: BuildOptions,
+    reload_tx: broadcast::Sender<()>,
+) -> Result<RecommendedWatcher> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    for dir in WATCH_DIRS {
+        watcher.watch(Path::new(dir), RecursiveMode::Recursive)?;
+    }
+
+    let runtime = tokio::runtime::Handle::current();
+    std::thread::spawn(move || {
+        while let Ok(event) = rx.recv() {
+            if !is_relevant(&event) {
+                continue;
+            }
+            while rx.recv_timeout(DEBOUNCE).is_ok() {}
+
+            println!("Change detected, rebuilding ...");
+            match runtime.block_on(build::run(&site, &options)) {
+                Ok(()) => {
+                    println!("Rebuild finished!");
+                    // 接続中のタブがなければ送信エラーになるが問題ない
+                    let _ = reload_tx.send(());
+                }
+                Err(e) => eprintln!("Rebuild failed: {e:#}"),
+            }
+        }
+    });
+
+    println!("Watching {} for changes", WATCH_DIRS.join(", "));
+    Ok(watcher)
+}
+
+/// 読み込みや属性の変更だけでは再ビルドしない（書き込み完了は対象）
+fn is_relevant(event: &notify::Result<notify::Event>) -> bool {
+    match event {
+        Ok(event) => match event.kind {
+            EventKind::Modify(ModifyKind::Metadata(_)) => false,
+            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) => true,
+            EventKind::Access(AccessKind::Close(AccessMode::Write)) => true,
+            _ => false,
+        },
+        Err(e) => {
+            eprintln!("Watch error: {e}");
+            false
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! 同じ入力から2回ビルドした `dist/` がバイト単位で一致することを確かめる
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use walkdir::WalkDir;
+
+const WORKSPACE_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../..");
+
+/// 2026-01-01T00:00:00Z
+const SOURCE_DATE_EPOCH: &str = "1767225600";
+
+const POSTS: [(&str, &str); 4] = [
+    (
+        "2026-01-10_b.md",
+        "+++\ntitle = \"RustでSSGを作る\"\nslug = \"rust-ssg\"\ndescription = \"b\"\n[taxonomies]\ntags = [\"Rust\", \"axum\"]\nlanguages = [\"ja\"]\n+++\n\nRustで静的サイトジェネレーターを作った。\n\n```rust\nfn main() {}\n```\n",
+    ),
+    (
+        "2026-01-10_a.md",
+        "+++\ntitle = \"RustとWASM\"\nslug = \"rust-wasm\"\ndescription = \"a\"\n[taxonomies]\ntags = [\"Rust\", \"Axum\", \"WASM\"]\nlanguages = [\"ja\"]\n+++\n\n同じ日付の記事。[SSG](/posts/rust-ssg/)\n",
+    ),
+    (
+        "2026-02-01_c.md",
+        "+++\ntitle = \"Neovim\"\nslug = \"neovim\"\ndescription = \"c\"\n[taxonomies]\ntags = [\"Neovim\", \"WASM\"]\nlanguages = [\"ja\"]\n+++\n\nNeovimの設定。\n",
+    ),
+    (
+        // `SOURCE_DATE_EPOCH` より後・実行時刻より前の予約投稿
+        "2026-02-02_d.md",
+        "+++\ntitle = \"予約投稿\"\nslug = \"scheduled\"\ndescription = \"d\"\npublish_at = \"2026-02-02\"\n[taxonomies]\ntags = [\"Rust\"]\nlanguages = [\"ja\"]\n+++\n\n公開予定の記事。\n",
+    ),
+];
+
+fn copy_dir(from: &Path, to: &Path) {
+    for entry in WalkDir::new(from).into_iter().filter_map(|e| e.ok()) {
+        let target = to.join(entry.path().strip_prefix(from).unwrap());
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target).unwrap();
+        } else {
+            fs::copy(entry.path(), &target).unwrap();
+        }
+    }
+}
+
+/// 小さなサイトを作る（OGP画像に使うフォントとアイコンはリポジトリのものを使う）
+fn create_site(site_dir: &Path) {
+    if site_dir.exists() {
+        fs::remove_dir_all(site_dir).unwrap();
+    }
+    let workspace = Path::new(WORKSPACE_DIR);
+    fs::create_dir_all(site_dir.join("content")).unwrap();
+    fs::create_dir_all(site_dir.join("pages")).unwrap();
+    fs::copy(
+        workspace.join("dnfolio.toml"),
+        site_dir.join("dnfolio.toml"),
+    )
+    .unwrap();
+    copy_dir(&workspace.join("assets"), &site_dir.join("assets"));
+    copy_dir(
+        &workspace.join("static/icons"),
+        &site_dir.join("static/icons"),
+    );
+
+    for (file_name, source) in POSTS {
+        fs::write(site_dir.join("content").join(file_name), source).unwrap();
+    }
+    fs::write(site_dir.join("pages/about.md"), "# About\n").unwrap();
+}
+
+fn build(site_dir: &Path) -> BTreeMap<PathBuf, Vec<u8>> {
+    let status = Command::new(env!("CARGO_BIN_EXE_dnfolio-ssg"))
+        .args(["build", "--no-cache"])
+        .current_dir(site_dir)
+        .env("SOURCE_DATE_EPOCH", SOURCE_DATE_EPOCH)
+        .status()
+        .unwrap();
+    assert!(status.success(), "build failed");
+
+    let dist_dir = site_dir.join("dist");
+    WalkDir::new(&dist_dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| {
+            (
+                entry.path().strip_prefix(&dist_dir).unwrap().to_path_buf(),
+                fs::read(entry.path()).unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_build_is_reproducible() {
+    // 前回の出力が残って差分を隠さないよう、別々のディレクトリに作り直してビルドする
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let [first, second] = ["reproducible-site-1", "reproducible-site-2"].map(|name| {
+        let site_dir = target_dir.join(name);
+        create_site(&site_dir);
+        build(&site_dir)
+    });
+
+    assert!(first.contains_key(Path::new("sitemap.xml")));
+    // 予約投稿は実行時刻ではなく `SOURCE_DATE_EPOCH` で判定する
+    assert!(!first.contains_key(Path::new("posts/scheduled/index.html")));
+    assert_eq!(
+        first.keys().collect::<Vec<_>>(),
+        second.keys().collect::<Vec<_>>()
+    );
+    for (path, contents) in &first {
+        assert!(
+            second[path] == *contents,
+            "{} differs between builds",
+            path.display()
+        );
+    }
+}
@@ -1094,6 +1094,15 @@ fn setup_folder_toggle_handlers() -> Result<()> {
                 let handler = Closure::wrap(Box::new(move |e: web_sys::MouseEvent| {
                     e.stop_propagation();
 
+                    // アーカイブページへのリンクのクリックは開閉しない
+                    if let Some(target) = e.target() {
+                        if let Some(target_el) = target.dyn_ref::<web_sys::Element>() {
+                            if target_el.closest(".folder-link").ok().flatten().is_some() {
+                                return;
+                            }
+                        }
+                    }
+
                     // 親の .folder-item に collapsed クラスをトグル
                     if let Some(parent) = el_clone.parent_element() {
                         if parent.class_list().contains("folder-item") {
@@ -51,12 +51,15 @@
 //!   - `statusline` - ステータスライン
 //!   - `commandline` - コマンドライン
 //! - `events` - イベントハンドラー
+//! - `live_reload` - 開発サーバーのライブリロード（デバッグビルドのみ）
 
 use wasm_bindgen::prelude::*;
 
 pub mod dom;
 pub mod error;
 pub mod events;
+#[cfg(debug_assertions)]
+mod live_reload;
 pub mod search;
 pub mod ui;
 pub mod vim;
@@ -100,6 +103,12 @@ fn init_app() -> Result<()> {
     // イベントハンドラーを登録
     events::setup_all_event_handlers()?;
 
+    // 開発ビルドのみ: `dnfolio serve` の再ビルド通知でリロード
+    #[cfg(debug_assertions)]
+    if let Err(e) = live_reload::init() {
+        web_sys::console::warn_1(&format!("Live reload unavailable: {e}").into());
+    }
+
     // 初期カーソルを設定
     setup_initial_cursor()?;
 
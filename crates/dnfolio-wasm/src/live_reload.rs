@@ -0,0 +1,40 @@
+//! 開発サーバー用ライブリロード
+//!
+//! `dnfolio serve` のSSEエンドポイントを購読し、再ビルド完了の通知で
+//! ページをリロードする。デバッグビルド（`wasm-pack --dev`）でのみ有効。
+
+use wasm_bindgen::JsCast;
+use wasm_bindgen::closure::Closure;
+use web_sys::{Event, EventSource, MessageEvent};
+
+use crate::error::Result;
+
+/// SSG側（`serve.rs`）と揃えるエンドポイント
+const RELOAD_ENDPOINT: &str = "/__dnfolio/reload";
+
+/// リロード通知の購読を開始する
+pub fn init() -> Result<()> {
+    let source = EventSource::new(RELOAD_ENDPOINT)?;
+
+    let on_message = Closure::wrap(Box::new(move |_: MessageEvent| {
+        if let Some(window) = web_sys::window() {
+            let _ = window.location().reload();
+        }
+    }) as Box<dyn FnMut(MessageEvent)>);
+    source.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    on_message.forget();
+
+    // サーバー再起動時はEventSourceが自動で再接続する。
+    // `dnfolio serve` 以外で配信されている場合（404等）は接続がCLOSEDになる
+    let source_for_error = source.clone();
+    let on_error = Closure::wrap(Box::new(move |_: Event| {
+        if source_for_error.ready_state() == EventSource::CLOSED {
+            web_sys::console::log_1(&"Live reload disconnected".into());
+        }
+    }) as Box<dyn FnMut(Event)>);
+    source.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+    on_error.forget();
+
+    web_sys::console::log_1(&"  ✓ Live reload enabled".into());
+    Ok(())
+}
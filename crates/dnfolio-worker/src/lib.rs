@@ -33,7 +33,7 @@ fn is_gone_path(path: &str) -> bool {
         })
 }
 
-fn build_redirect_url(url: &Url, target_path: &str) -> Result<Url> {
+fn build_redirect_url(target_path: &str) -> Result<Url> {
     debug_assert!(
         target_path.starts_with('/'),
         "target_path must be absolute: {target_path}"
@@ -80,7 +80,7 @@ pub async fn fetch(req: Request, env: Env, _ctx: Context) -> Result<Response> {
     // パスベースのリダイレクト・Gone判定はホストより先に行う。
     // これにより dnfolio.dev 経由のアクセスでも最終URLへ1ホップでリダイレクトできる。
     if let Some(target_path) = redirect_target(path) {
-        return redirect_response(build_redirect_url(&url, target_path)?);
+        return redirect_response(build_redirect_url(target_path)?);
     }
 
     // 廃止済みURLには410 Goneを返し、Googleにインデックス除外を促す
@@ -90,7 +90,7 @@ pub async fn fetch(req: Request, env: Env, _ctx: Context) -> Result<Response> {
 
     // レガシードメインからのアクセスを正規ドメインへ転送
     if LEGACY_HOSTS.iter().any(|legacy_host| legacy_host == &host) {
-        return redirect_response(build_redirect_url(&url, path)?);
+        return redirect_response(build_redirect_url(path)?);
     }
 
     env.assets("ASSETS")?.fetch_request(req).await